@@ -0,0 +1,148 @@
+//! Timings of the hot paths of the simulation, run with `cargo bench`.
+//! Compare runs across versions with `cargo bench -- --save-baseline <name>`
+//! and `cargo bench -- --baseline <name>`.
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use simulation::{
+    actions::cells_plugin::find_closest_element,
+    core::{config::SimulationConfig, headless::build_headless_app, network::*},
+    entities::cells::Cell,
+    utils::{ring_buffer::{RingBuffer, RollingStats}, spatial_grid::SpatialGrid},
+};
+
+const POPULATIONS: [usize; 3] = [1_000, 10_000, 50_000];
+
+/// Points spread over a board as crowded as the benchmark scene.
+fn random_points(count: usize, rng: &mut StdRng) -> Vec<Vec2> {
+    let half_side = 1120. * (count as f32 / 10_000.).sqrt();
+    (0..count)
+        .map(|_| Vec2::new(rng.gen_range(-half_side..half_side), rng.gen_range(-half_side..half_side)))
+        .collect()
+}
+
+fn spatial_queries(c: &mut Criterion) {
+    let mut group = c.benchmark_group("spatial");
+    let mut rng = StdRng::seed_from_u64(0);
+    for count in POPULATIONS {
+        let points = random_points(count, &mut rng);
+        let centers = random_points(100, &mut rng);
+        group.bench_with_input(BenchmarkId::new("find_closest_element", count), &points, |b, points| {
+            b.iter(|| {
+                for &center in &centers {
+                    black_box(find_closest_element(points, center));
+                }
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("grid_build", count), &points, |b, points| {
+            b.iter(|| SpatialGrid::new(100., black_box(points)))
+        });
+        let grid = SpatialGrid::new(100., &points);
+        group.bench_with_input(BenchmarkId::new("grid_closest_within", count), &grid, |b, grid| {
+            b.iter(|| {
+                for &center in &centers {
+                    black_box(grid.closest_within(center, 100.));
+                }
+            })
+        });
+    }
+    group.finish();
+}
+
+fn brain_activation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("brains");
+    let mut rng = StdRng::seed_from_u64(1);
+    for count in POPULATIONS {
+        let positions = random_points(count, &mut rng);
+        let targets = random_points(count, &mut rng);
+        let mut cells: Vec<Cell> = (0..count).map(|_| Cell { energy: 50. }).collect();
+        group.bench_function(BenchmarkId::new("scalar", count), |b| {
+            b.iter(|| {
+                for ((cell, position), target) in cells.iter_mut().zip(&positions).zip(&targets) {
+                    black_box(cell.activate(position.extend(0.), *target));
+                }
+            })
+        });
+        let mut batched = BatchedNetworks::default();
+        group.bench_function(BenchmarkId::new("batched", count), |b| {
+            b.iter(|| {
+                batched.clear();
+                for (i, ((cell, position), target)) in cells.iter().zip(&positions).zip(&targets).enumerate() {
+                    batched.push(Entity::from_raw(i as u32), cell, position.extend(0.), *target);
+                }
+                batched.evaluate();
+                black_box(batched.results().count());
+            })
+        });
+    }
+    group.finish();
+}
+
+fn seed_spawning(c: &mut Criterion) {
+    let mut group = c.benchmark_group("seeds");
+    group.sample_size(10);
+    for count in POPULATIONS {
+        // A grid of seeds of about `count` on an otherwise empty board.
+        let side = 10. * (count as f32).sqrt();
+        let config = SimulationConfig {
+            rng_seed: 0,
+            board_width: side,
+            board_height: side,
+            seeds_step: 10.,
+            initial_plants: 0,
+            ..default()
+        };
+        group.bench_with_input(BenchmarkId::new("grid", count), &config, |b, config| {
+            b.iter_batched(|| build_headless_app(config.clone()), |mut app| app.update(), BatchSize::PerIteration)
+        });
+    }
+    group.finish();
+}
+
+fn ring_buffer(c: &mut Criterion) {
+    let mut group = c.benchmark_group("ring_buffer");
+    for capacity in [100, 10_000] {
+        group.bench_with_input(BenchmarkId::new("push", capacity), &capacity, |b, &capacity| {
+            let mut buffer = RingBuffer::new(capacity);
+            b.iter(|| {
+                for i in 0..1_000 {
+                    buffer.push(black_box(i));
+                }
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("rolling_stats", capacity), &capacity, |b, &capacity| {
+            let mut stats = RollingStats::new(capacity);
+            b.iter(|| {
+                for i in 0..1_000 {
+                    stats.push(black_box(i as f64));
+                }
+                stats.mean()
+            })
+        });
+        let mut buffer = RingBuffer::new(capacity);
+        buffer.extend(0..capacity);
+        group.bench_with_input(BenchmarkId::new("iterate", capacity), &buffer, |b, buffer| {
+            b.iter(|| buffer.iter().sum::<usize>())
+        });
+    }
+    group.finish();
+}
+
+fn headless_tick(c: &mut Criterion) {
+    let mut group = c.benchmark_group("tick");
+    group.sample_size(10).measurement_time(Duration::from_secs(20));
+    for count in POPULATIONS {
+        let mut app = build_headless_app(SimulationConfig::benchmark_with_cells(count as u32));
+        // Startup spawns the world, it is not part of a tick.
+        app.update();
+        group.bench_function(BenchmarkId::new("benchmark_scene", count), |b| b.iter(|| app.update()));
+    }
+    group.finish();
+}
+
+criterion_group!(benches, spatial_queries, brain_activation, seed_spawning, ring_buffer, headless_tick);
+criterion_main!(benches);
@@ -3,35 +3,95 @@ use bevy::{
 };
 
 use crate::{
-  entities::world_board::*,
+  core::{clock::WorldClock, config::SimulationConfig, fields::ScalarFields},
+  entities::{board_map::BoardMap, world_board::*},
 };
 
+const BOARD_COLOR: Color = Color::rgb(0.25, 0.25, 0.75);
+const BACKGROUND_COLOR: Color = Color::rgb(0.106, 0.106, 0.106);
+/// How much of the season colour shows through.
+const SEASON_TINT_STRENGTH: f32 = 0.35;
+/// Brightness of the board at midnight.
+const NIGHT_SHADE: f32 = 0.35;
+
 pub struct WorldBoardPlugin;
 
 impl Plugin for WorldBoardPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(Startup, board_spawn_action)
-            .insert_resource(ClearColor(Color::hex("#1B1B1B").unwrap()));
+            .add_systems(Update, board_tint_action)
+            .insert_resource(ClearColor(BACKGROUND_COLOR));
     }
 }
 
-fn board_spawn_action(mut commands: Commands) {
-    let board_rect = Rect::new(-250., -250., 250., 250.);
+pub(crate) fn board_spawn_action(
+    mut commands: Commands,
+    config: Res<SimulationConfig>,
+    mut images: Option<ResMut<Assets<Image>>>,
+) {
+    for definition in config.board_definitions() {
+        let board_rect = definition.rect();
+        let map = definition.image.as_deref().and_then(|path| {
+            BoardMap::load(path)
+                .map_err(|err| warn!("Board image of `{}` ignored: {err}", definition.name))
+                .ok()
+        });
 
-    commands.spawn((
-        SpriteBundle {
+        let mut sprite = SpriteBundle {
             sprite: Sprite {
-                color: Color::rgb(0.25, 0.25, 0.75),
-                rect: Some(board_rect),
+                color: BOARD_COLOR,
+                custom_size: Some(board_rect.size()),
                 ..default()
             },
-            transform: Transform::from_translation(Vec3::new(0., 0., 0.)),
+            transform: Transform::from_translation(board_rect.center().extend(0.)),
             ..default()
-        },
-        WorldBoard {
-            settings: BoardSettings {
-                rect: board_rect
-            }
-        },
-    ));
+        };
+        // Headless runs have no images, the map still shapes the board there.
+        if let (Some(map), Some(images)) = (&map, images.as_mut()) {
+            sprite.texture = images.add(map.to_image());
+        }
+
+        commands.spawn((
+            sprite,
+            WorldBoard {
+                settings: BoardSettings {
+                    name: definition.name,
+                    rect: board_rect,
+                    map,
+                    seeds_grid: definition.seeds_grid,
+                    seeds_step: definition.seeds_step,
+                    initial_cells: definition.initial_cells,
+                    initial_predators: definition.initial_predators,
+                    initial_plants: definition.initial_plants,
+                }
+            },
+            ScalarFields::new(board_rect, config.field_cell_size, &config.field_layers),
+        ));
+    }
+}
+
+/// Darkens the board and background at night and shifts them towards the season colour.
+fn board_tint_action(
+    clock: Res<WorldClock>,
+    mut clear_color: ResMut<ClearColor>,
+    mut boards: Query<&mut Sprite, With<WorldBoard>>,
+) {
+    if !clock.is_changed() {
+        return;
+    }
+    let shade = NIGHT_SHADE + (1. - NIGHT_SHADE) * clock.daylight;
+    let season = clock.season.tint();
+    let tint = |base: Color| {
+        let tinted = Vec3::new(
+            base.r() * (1. - SEASON_TINT_STRENGTH + SEASON_TINT_STRENGTH * season.r()),
+            base.g() * (1. - SEASON_TINT_STRENGTH + SEASON_TINT_STRENGTH * season.g()),
+            base.b() * (1. - SEASON_TINT_STRENGTH + SEASON_TINT_STRENGTH * season.b()),
+        ) * shade;
+        Color::rgb(tinted.x, tinted.y, tinted.z)
+    };
+
+    clear_color.0 = tint(BACKGROUND_COLOR);
+    for mut sprite in boards.iter_mut() {
+        sprite.color = tint(BOARD_COLOR);
+    }
 }
\ No newline at end of file
@@ -1,26 +1,99 @@
+use std::collections::HashSet;
+
 use bevy::math::Vec3Swizzles;
-use bevy_rapier2d::prelude::*;
 use bevy::{
-    input::{mouse::MouseButtonInput, ButtonState},
     prelude::*,
     sprite::MaterialMesh2dBundle,
-    window::Window,
 };
-use bevy_rapier2d::geometry::Collider;
+use bevy_rapier2d::geometry::{ActiveCollisionTypes, ActiveEvents, Collider, ColliderScale};
 use bevy_rapier2d::pipeline::CollisionEvent;
+use rand::Rng;
 
 use crate::{
-    cameras::main_camera::*,
-    core::network::*,
-    entities::{cells::*, seed::*, world_board::*},
-    ui::info_board::InfoBoardSettings,
+    actions::{corpses_plugin::SpawnCorpseEvent, seeds_plugin::seeds_spawn_action},
+    core::{
+        clock::WorldClock,
+        config::SimulationConfig,
+        fields::{ScalarFields, PHEROMONES},
+        lineage::*,
+        network::*,
+        shared_handles::SharedHandles,
+        simulation::{SimulationRng, SimulationSet, SimulationTick},
+        species::{SpeciesId, SpeciesRegistry},
+    },
+    entities::{cells::*, collision_groups::*, genome::Genome, seed::*, terrain::WorldLayout, world_board::*},
+    utils::spatial_grid::SpatialGrid,
 };
 
+const OPEN_POSITION_TRIES: usize = 100;
+
 pub struct CellsPlugin;
 
 impl Plugin for CellsPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, (cells_move_action, cells_spawn_action, update_metrics_system, check_collisions_system));
+        app.add_event::<SpawnCellEvent>()
+            .add_event::<CellAteEvent>()
+            .add_systems(PostStartup, cells_initial_spawn_action.after(seeds_spawn_action))
+            .add_systems(
+                Update,
+                (
+                    cells_sense_system,
+                    cells_think_system,
+                    cells_act_system,
+                    check_collisions_system,
+                    predation_system,
+                    cells_division_action,
+                    cells_death_action,
+                    cells_spawn_action,
+                )
+                    .chain()
+                    .in_set(SimulationSet),
+            );
+    }
+}
+
+/// Asks for a new cell at a world position; positions outside the board are ignored.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct SpawnCellEvent {
+    pub position: Vec2,
+    pub parent: Option<CellId>,
+    /// Starting energy, `SimulationConfig::cell_initial_energy` when `None`.
+    pub energy: Option<f32>,
+    /// `SimulationConfig::founder_genome` when `None`.
+    pub genome: Option<Genome>,
+    pub predator: bool,
+}
+
+/// A cell ate something.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct CellAteEvent {
+    pub id: CellId,
+    pub food: Food,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Food {
+    Seed,
+    Prey(CellId),
+    Corpse,
+}
+
+impl SpawnCellEvent {
+    pub fn new(position: Vec2) -> Self {
+        Self {
+            position,
+            parent: None,
+            energy: None,
+            genome: None,
+            predator: false,
+        }
+    }
+
+    pub fn predator(position: Vec2) -> Self {
+        Self {
+            predator: true,
+            ..Self::new(position)
+        }
     }
 }
 
@@ -30,7 +103,7 @@ fn distance_squared(point1: Vec2, point2: Vec2) -> f32 {
     dx * dx + dy * dy
 }
 
-fn find_closest_element(points: &[Vec2], center: Vec2) -> Option<(Vec2)> {
+pub fn find_closest_element(points: &[Vec2], center: Vec2) -> Option<Vec2> {
     let mut closest_distance = f32::MAX;
     let mut closest_element: Option<Vec2> = None;
 
@@ -45,109 +118,440 @@ fn find_closest_element(points: &[Vec2], center: Vec2) -> Option<(Vec2)> {
     closest_element
 }
 
-fn cells_move_action(
-    mut commands: Commands,
-    mut query: Query<(&mut Transform, &mut Cell, Entity), With<Cell>>,
-    mut seeds: Query<(&Transform, Entity), (With<Seed>, Without<Cell>)>,
-    boards: Query<&WorldBoard>,
+/// The point closest to the centre of mass of all `points`.
+pub fn find_central_element(points: &[Vec2]) -> Option<Vec2> {
+    let summ_of_points = points.iter().fold(Vec2::ZERO, |acc, point| acc + *point);
+    let center_of_points = summ_of_points / points.len() as f32;
+    find_closest_element(points, center_of_points)
+}
+
+/// What the sensors report every tick, read by the brain.
+pub(crate) fn cells_sense_system(
+    mut cells: Query<(&Transform, &Genome, Has<Predator>, &mut CellSenses)>,
+    seeds: Query<&Transform, (With<Seed>, Without<Cell>)>,
+    boards: Query<(&WorldBoard, &ScalarFields)>,
+    config: Res<SimulationConfig>,
 ) {
-    let summ_of_seeds = seeds
-        .iter()
-        .map(|(transform, _)| transform.translation)
-        .fold((0., 0.), |acc, x| (acc.0 + x.x, acc.1 + x.y));
-    let len = seeds.iter().len() as f32;
-    let center_of_seeds = Vec2::new(summ_of_seeds.0 / len, summ_of_seeds.1 / len);
-
-    let cells_positions: Vec<Vec2> = seeds
-        .iter()
-        .map(|(transform, _)| transform.translation.xy())
-        .collect();
-    let closed_seed = find_closest_element(cells_positions.as_slice(), center_of_seeds);
+    let boards: Vec<_> = boards.iter().collect();
 
-    //time.seconds_since_starttime();
-    for (mut transform, shape, entity) in query.iter_mut() {
-        let moving_vect = shape.into_inner().activate(
-            transform.translation,
-            closed_seed.unwrap_or(Vec2::default()),
-        );
-        transform.translation = transform.translation + moving_vect;
+    // Cells only see food on their own board.
+    let mut seeds_positions = vec![Vec::new(); boards.len()];
+    for transform in seeds.iter() {
+        let position = transform.translation.xy();
+        if let Some(index) = board_index_at(boards.iter().map(|(board, _)| *board), position) {
+            seeds_positions[index].push(position);
+        }
+    }
+    let mut prey_positions = vec![Vec::new(); boards.len()];
+    for (transform, ..) in cells.iter().filter(|(_, _, is_predator, _)| !is_predator) {
+        let position = transform.translation.xy();
+        if let Some(index) = board_index_at(boards.iter().map(|(board, _)| *board), position) {
+            prey_positions[index].push(position);
+        }
+    }
+    let closed_seeds: Vec<Option<Vec2>> = seeds_positions.iter().map(|seeds| find_central_element(seeds)).collect();
+    let closed_preys: Vec<Option<Vec2>> = prey_positions.iter().map(|prey| find_central_element(prey)).collect();
+    // Sensor lookups go through a grid, scanning all food per cell does not scale.
+    let grid_size = config.cell_sensor_range;
+    let seeds_grids: Vec<SpatialGrid> = seeds_positions.iter().map(|seeds| SpatialGrid::new(grid_size, seeds)).collect();
+    let prey_grids: Vec<SpatialGrid> = prey_positions.iter().map(|prey| SpatialGrid::new(grid_size, prey)).collect();
+
+    cells.par_iter_mut().for_each(|(transform, genome, is_predator, mut senses)| {
+        let position = transform.translation.xy();
+        let Some(index) = board_index_at(boards.iter().map(|(board, _)| *board), position) else {
+            *senses = CellSenses::default();
+            return;
+        };
+        let fields = boards[index].1;
+        let (food, shared_target) = if is_predator {
+            (&prey_grids[index], closed_preys[index])
+        } else {
+            (&seeds_grids[index], closed_seeds[index])
+        };
+
+        // Food within sensor range wins over a pheromone trail for predators,
+        // which wins over the shared target in the middle of the food.
+        // Predators skip themselves naturally, they are not in the prey list.
+        let trail = fields
+            .layer(PHEROMONES)
+            .filter(|_| is_predator)
+            .map(|layer| fields.gradient(layer, position))
+            .filter(|gradient| gradient.length_squared() > f32::EPSILON)
+            .map(|gradient| position + gradient.normalize() * genome.sensor_range);
+        *senses = CellSenses {
+            board: Some(index),
+            target: food
+                .closest_within(position, genome.sensor_range)
+                .or(trail)
+                .or(shared_target),
+        };
+    });
+}
+
+/// Runs every brain on its senses; brains only touch their own cell, so they run in parallel.
+pub(crate) fn cells_think_system(
+    mut cells: Query<(&Transform, &mut Cell, &CellSenses, &mut CellIntent, Entity)>,
+    config: Res<SimulationConfig>,
+    mut batched: Local<BatchedNetworks>,
+) {
+    match config.brain_backend {
+        BrainBackend::Scalar => {
+            cells.par_iter_mut().for_each(|(transform, mut cell, senses, mut intent, _)| {
+                intent.direction = cell.activate(transform.translation, senses.target.unwrap_or_default());
+            });
+        }
+        BrainBackend::Batched => {
+            batched.clear();
+            for (transform, cell, senses, _, entity) in cells.iter() {
+                batched.push(entity, cell, transform.translation, senses.target.unwrap_or_default());
+            }
+            batched.evaluate();
+            for (entity, direction) in batched.results() {
+                if let Ok((.., mut intent, _)) = cells.get_mut(entity) {
+                    intent.direction = direction;
+                }
+            }
+        }
+    }
+}
+
+/// What `cells_act_system` reads and moves of every cell.
+type ActingCell = (
+    &'static mut Transform,
+    &'static mut Cell,
+    &'static Genome,
+    &'static CellId,
+    Has<Predator>,
+    &'static CellSenses,
+    &'static CellIntent,
+    Entity,
+);
+
+/// Moves cells the way their brain decided and charges them for it.
+pub(crate) fn cells_act_system(
+    mut cells: Query<ActingCell>,
+    mut boards: Query<(&WorldBoard, &mut ScalarFields)>,
+    config: Res<SimulationConfig>,
+    clock: Res<WorldClock>,
+    mut died_events: EventWriter<CellDiedEvent>,
+) {
+    let metabolism = config.cell_move_cost * clock.season.metabolism();
+    let mut boards: Vec<_> = boards.iter_mut().collect();
+
+    for (mut transform, mut shape, genome, &id, is_predator, senses, intent, entity) in cells.iter_mut() {
+        let Some(index) = senses.board else {
+            died_events.send(CellDiedEvent { entity, id, cause: DeathCause::LeftBoard });
+            continue;
+        };
+        let (board, fields) = &mut boards[index];
+        let position = transform.translation.xy();
+
+        // Rough terrain slows cells down and makes every tick there more expensive.
+        let move_cost = config.world.move_cost(position).max(f32::EPSILON);
+        let step = (intent.direction * genome.speed / move_cost).xy();
+        transform.translation += obstacle_free_step(&config.world, position, step, genome.radius).extend(0.);
+        // Faster and bigger than the founders costs more per tick.
+        shape.energy -= metabolism * move_cost * genome.speed * genome.radius / config.cell_radius;
 
         let shape_position = transform.translation.xy();
-        let world_board_rect = boards.single().settings.rect;
+        if let Some(layer) = fields.layer(PHEROMONES).filter(|_| !is_predator) {
+            fields.deposit(layer, shape_position, config.cell_pheromone_deposit);
+        }
+
+        let cause = if !board.contains(shape_position) {
+            Some(DeathCause::LeftBoard)
+        } else if shape.energy <= 0. {
+            Some(DeathCause::Starvation)
+        } else {
+            None
+        };
+        if let Some(cause) = cause {
+            died_events.send(CellDiedEvent { entity, id, cause });
+        }
+    }
+}
+
+/// The part of `step` a cell can take without running into an obstacle, sliding
+/// along it when only one axis is blocked. Cells already overlapping one move freely
+/// so they can get out.
+fn obstacle_free_step(world: &WorldLayout, position: Vec2, step: Vec2, radius: f32) -> Vec2 {
+    if world.blocks(position, radius) {
+        return step;
+    }
+    [step, Vec2::new(step.x, 0.), Vec2::new(0., step.y)]
+        .into_iter()
+        .find(|step| !world.blocks(position + *step, radius))
+        .unwrap_or(Vec2::ZERO)
+}
 
-        if !world_board_rect.contains(shape_position) {
-            commands.entity(entity).despawn();
+fn cells_division_action(
+    mut query: Query<(&Transform, &mut Cell, &Genome, &CellId, Has<Predator>)>,
+    boards: Query<&WorldBoard>,
+    config: Res<SimulationConfig>,
+    mut rng: ResMut<SimulationRng>,
+    mut spawn_events: EventWriter<SpawnCellEvent>,
+) {
+    for (transform, mut cell, genome, &id, is_predator) in query.iter_mut() {
+        let position = transform.translation.xy();
+        let on_board = boards.iter().any(|board| board.contains(position));
+        if cell.energy < config.cell_division_energy || !on_board {
+            continue;
         }
+
+        cell.energy /= 2.;
+        let angle = rng.0.gen_range(0. ..std::f32::consts::TAU);
+        spawn_events.send(SpawnCellEvent {
+            position: position + Vec2::from_angle(angle) * config.cell_radius,
+            parent: Some(id),
+            energy: Some(cell.energy),
+            genome: Some(genome.mutated(&mut rng.0, config.mutation_rate)),
+            predator: is_predator,
+        });
     }
 }
 
-fn cells_spawn_action(
+/// Despawns dead cells; cells that died on the board leave a corpse behind.
+fn cells_death_action(
     mut commands: Commands,
-    mut events: EventReader<MouseButtonInput>,
-    mut meshes: ResMut<Assets<Mesh>>,
+    mut events: EventReader<CellDiedEvent>,
+    cells: Query<(&Transform, &Cell, &Genome)>,
+    config: Res<SimulationConfig>,
+    mut corpse_events: EventWriter<SpawnCorpseEvent>,
+) {
+    let mut despawned = HashSet::new();
+    for ev in events.read() {
+        if !despawned.insert(ev.entity) {
+            continue;
+        }
+        commands.entity(ev.entity).despawn();
+
+        // Predation leaves its own corpse with what the predator did not take.
+        if matches!(ev.cause, DeathCause::LeftBoard | DeathCause::Predation) {
+            continue;
+        }
+        if let Ok((transform, cell, genome)) = cells.get(ev.entity) {
+            let size = genome.radius / config.cell_radius;
+            corpse_events.send(SpawnCorpseEvent {
+                position: transform.translation.xy(),
+                energy: cell.energy.max(0.) + config.corpse_body_energy * size * size,
+                radius: genome.radius,
+            });
+        }
+    }
+}
+
+pub(crate) fn cells_initial_spawn_action(
+    mut events: EventWriter<SpawnCellEvent>,
+    boards: Query<&WorldBoard>,
+    config: Res<SimulationConfig>,
+    mut rng: ResMut<SimulationRng>,
+) {
+    for board in boards.iter() {
+        for _ in 0..board.settings.initial_cells {
+            events.send(SpawnCellEvent::new(random_open_position(board, &config.world, &mut rng)));
+        }
+        for _ in 0..board.settings.initial_predators {
+            events.send(SpawnCellEvent::predator(random_open_position(board, &config.world, &mut rng)));
+        }
+    }
+}
+
+/// A random position on the board, with a few tries to land on open ground
+/// since shaped boards can be mostly water or walls.
+pub(crate) fn random_open_position(board: &WorldBoard, world: &WorldLayout, rng: &mut SimulationRng) -> Vec2 {
+    let board_rect = board.settings.rect;
+    let mut position = board_rect.center();
+    for _ in 0..OPEN_POSITION_TRIES {
+        position = Vec2::new(
+            rng.0.gen_range(board_rect.min.x..board_rect.max.x),
+            rng.0.gen_range(board_rect.min.y..board_rect.max.y),
+        );
+        if board.contains(position) && !world.blocks(position, 0.) {
+            break;
+        }
+    }
+    position
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn cells_spawn_action(
+    mut commands: Commands,
+    mut events: EventReader<SpawnCellEvent>,
     mut materials: ResMut<Assets<ColorMaterial>>,
-    windows: Query<&Window>,
-    boards: Query<(&WorldBoard, &Transform), With<WorldBoard>>,
-    camera_q: Query<(&Camera, &GlobalTransform), With<GameCapCamera>>,
+    mut handles: ResMut<SharedHandles>,
+    boards: Query<&WorldBoard>,
+    config: Res<SimulationConfig>,
+    tick: Res<SimulationTick>,
+    mut lineage: ResMut<LineageRecords>,
+    mut species: ResMut<SpeciesRegistry>,
 ) {
     for ev in events.read() {
-        match ev.state {
-            ButtonState::Pressed => {
-                let (camera, camera_transform) = camera_q.single();
-                let (world_board, _) = boards.single();
-                let window = windows.single();
-
-                if let Some(world_position) = window
-                    .cursor_position()
-                    .and_then(|cursor| camera.viewport_to_world(camera_transform, cursor))
-                    .map(|ray| ray.origin.truncate())
-                {
-                    let board_rect = world_board.settings.rect;
-                    if board_rect.contains(world_position) {
-                        commands.spawn((
-                            MaterialMesh2dBundle {
-                                mesh: meshes.add(Circle { radius: 15.0 }).into(),
-                                material: materials.add(ColorMaterial::from(Color::PURPLE)),
-                                transform: Transform::from_xyz(
-                                    world_position.x,
-                                    world_position.y,
-                                    1.,
-                                ),
-                                ..default()
-                            },
-                            Cell {},
-                        ))
-                            .insert(Collider::ball(15.));
-                    }
-                }
-            }
-            ButtonState::Released => {}
+        let on_board = boards.iter().any(|board| board.contains(ev.position));
+        if !on_board || config.world.blocks(ev.position, 0.) {
+            continue;
         }
+        let id = lineage.register_birth(ev.parent, tick.0);
+        let genome = ev.genome.unwrap_or_else(|| config.founder_genome());
+        let species_id = species.classify(&genome, ev.predator, config.species_threshold);
+        let cell = Cell {
+            energy: ev.energy.unwrap_or(config.cell_initial_energy),
+        };
+        spawn_cell(
+            &mut commands,
+            &mut handles,
+            &mut materials,
+            &species,
+            ev.position,
+            cell,
+            id,
+            genome,
+            species_id,
+            ev.predator,
+        );
     }
 }
 
-fn update_metrics_system(
-    mut cells: Query<&Cell>,
-    mut seeds: Query<&Seed>,
-    mut info_board_settings: ResMut<InfoBoardSettings>,
+/// Spawns a cell entity as it is.
+#[allow(clippy::too_many_arguments)]
+fn spawn_cell(
+    commands: &mut Commands,
+    handles: &mut SharedHandles,
+    materials: &mut Assets<ColorMaterial>,
+    species: &SpeciesRegistry,
+    position: Vec2,
+    cell: Cell,
+    id: CellId,
+    genome: Genome,
+    species_id: SpeciesId,
+    predator: bool,
 ) {
-    info_board_settings.world_metrics.cells_count.push(cells.iter().count() as f64);
-    info_board_settings.world_metrics.seeds_count.push(seeds.iter().count() as f64);
+    let color = species.get(species_id).map_or(Color::PURPLE, |species| species.color);
+    let mesh = if predator {
+        handles.unit_triangle.clone()
+    } else {
+        handles.unit_circle.clone()
+    };
+    let mut entity = commands.spawn((
+        MaterialMesh2dBundle {
+            mesh: mesh.into(),
+            material: handles.species_material(materials, species_id, color),
+            transform: Transform::from_xyz(position.x, position.y, 1.)
+                .with_scale(Vec3::new(genome.radius, genome.radius, 1.)),
+            ..default()
+        },
+        cell,
+        id,
+        genome,
+        species_id,
+        CellSenses::default(),
+        CellIntent::default(),
+    ));
+    // The transform scale only sizes the shared unit mesh.
+    entity.insert((Collider::ball(genome.radius), ColliderScale::Absolute(Vec2::ONE)));
+
+    if predator {
+        // Cells have no rigid body, so contacts between two of them must be enabled explicitly.
+        entity.insert((
+            Predator,
+            PREDATOR_COLLISION_GROUPS,
+            ActiveEvents::COLLISION_EVENTS,
+            ActiveCollisionTypes::default() | ActiveCollisionTypes::STATIC_STATIC,
+        ));
+    } else {
+        entity.insert(CELL_COLLISION_GROUPS);
+    }
 }
 
 fn check_collisions_system(
     mut commands: Commands,
     mut collision_events: EventReader<CollisionEvent>,
-    query: Query<Entity, (With<Collider>, Without<Cell>)>,
+    seeds: Query<Entity, With<Seed>>,
+    mut cells: Query<(&mut Cell, &CellId), Without<Predator>>,
+    config: Res<SimulationConfig>,
+    mut ate_events: EventWriter<CellAteEvent>,
 ) {
-    for collision_event in collision_events.read() {
-        if let CollisionEvent::Started(entity1, entity2, _) = collision_event {
-            let entities = [entity1, entity2];
-            for &entity in &entities {
-                if let Ok(entity) = query.get(*entity) {
-                    commands.entity(entity).despawn();
-                }
-            }
+    // The cell with the lowest id gets a seed that several reach in the same tick.
+    let mut contacts: Vec<(CellId, Entity, Entity)> = started_pairs(&mut collision_events)
+        .filter(|(seed, _)| seeds.contains(*seed))
+        .filter_map(|(seed, cell)| Some((*cells.get(cell).ok()?.1, cell, seed)))
+        .collect();
+    contacts.sort_by_key(|(id, _, _)| *id);
+
+    let mut eaten = HashSet::new();
+    for (id, cell, seed) in contacts {
+        if eaten.insert(seed) {
+            commands.entity(seed).despawn();
+            cells.get_mut(cell).unwrap().0.energy += config.seed_energy;
+            ate_events.send(CellAteEvent { id, food: Food::Seed });
+        }
+    }
+}
+
+/// The pairs of entities that started touching, both ways round. The physics
+/// world reports them in the order of its own handles, so callers sort them by
+/// something of their own before the order can matter.
+pub(crate) fn started_pairs<'a>(
+    collision_events: &'a mut EventReader<CollisionEvent>,
+) -> impl Iterator<Item = (Entity, Entity)> + 'a {
+    collision_events.read().flat_map(|collision_event| match collision_event {
+        CollisionEvent::Started(entity1, entity2, _) => vec![(*entity1, *entity2), (*entity2, *entity1)],
+        CollisionEvent::Stopped(..) => Vec::new(),
+    })
+}
+
+/// A predator touching a cell attacks it: the attack always costs energy and
+/// succeeds with `predator_success_chance` if the prey is smaller or weaker.
+#[allow(clippy::too_many_arguments)]
+fn predation_system(
+    mut collision_events: EventReader<CollisionEvent>,
+    mut predators: Query<(&mut Cell, &Genome, &CellId), With<Predator>>,
+    mut prey: Query<(&mut Cell, &Genome, &CellId, &Transform), Without<Predator>>,
+    config: Res<SimulationConfig>,
+    mut rng: ResMut<SimulationRng>,
+    mut died_events: EventWriter<CellDiedEvent>,
+    mut ate_events: EventWriter<CellAteEvent>,
+    mut corpse_events: EventWriter<SpawnCorpseEvent>,
+) {
+    // Attacks draw from the rng, so they are made in the order of the ids involved.
+    let mut attacks: Vec<(CellId, CellId, Entity, Entity)> = started_pairs(&mut collision_events)
+        .filter_map(|(hunter, target)| {
+            Some((*predators.get(hunter).ok()?.2, *prey.get(target).ok()?.2, hunter, target))
+        })
+        .collect();
+    attacks.sort_by_key(|(predator_id, victim_id, _, _)| (*predator_id, *victim_id));
+
+    let mut eaten = HashSet::new();
+    for (predator_id, victim_id, hunter, target) in attacks {
+        if eaten.contains(&target) {
+            continue;
+        }
+        let (mut predator, predator_genome, _) = predators.get_mut(hunter).unwrap();
+        let (mut victim, victim_genome, _, victim_transform) = prey.get_mut(target).unwrap();
+
+        predator.energy -= config.predator_attack_cost;
+        let smaller = victim_genome.radius < predator_genome.radius * config.predator_size_ratio;
+        let weaker = victim.energy < predator.energy;
+        if (smaller || weaker) && rng.0.gen_bool(config.predator_success_chance as f64) {
+            eaten.insert(target);
+            let energy = victim.energy.max(0.);
+            predator.energy += energy * config.predator_energy_gain;
+            // The body is eaten, so the corpse only holds what the predator did not take.
+            corpse_events.send(SpawnCorpseEvent {
+                position: victim_transform.translation.xy(),
+                energy: energy * (1. - config.predator_energy_gain),
+                radius: victim_genome.radius,
+            });
+            // Dead cells must not divide before they are despawned.
+            victim.energy = 0.;
+            died_events.send(CellDiedEvent {
+                entity: target,
+                id: victim_id,
+                cause: DeathCause::Predation,
+            });
+            ate_events.send(CellAteEvent {
+                id: predator_id,
+                food: Food::Prey(victim_id),
+            });
         }
     }
-}
\ No newline at end of file
+}
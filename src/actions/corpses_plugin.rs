@@ -0,0 +1,141 @@
+use std::collections::HashSet;
+
+use bevy::{prelude::*, sprite::MaterialMesh2dBundle};
+use bevy_rapier2d::geometry::{ActiveEvents, Collider, ColliderScale, Sensor};
+use bevy_rapier2d::pipeline::CollisionEvent;
+use rand::Rng;
+
+use crate::{
+    actions::{
+        cells_plugin::{cells_spawn_action, started_pairs, CellAteEvent, Food},
+        seeds_plugin::SpawnSeedEvent,
+    },
+    core::{
+        config::SimulationConfig,
+        fields::{ScalarFields, NUTRIENTS},
+        shared_handles::SharedHandles,
+        simulation::{SimulationRng, SimulationSet},
+    },
+    entities::{cells::*, collision_groups::CORPSE_COLLISION_GROUPS, corpse::*},
+};
+
+pub struct CorpsesPlugin;
+
+impl Plugin for CorpsesPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<SpawnCorpseEvent>()
+            // The world systems run in one fixed order, as they share the random
+            // source and the order things spawn in, so a run can be reproduced.
+            .add_systems(
+                Update,
+                (corpses_spawn_action, scavenging_system, corpses_decay_action)
+                    .chain()
+                    .after(cells_spawn_action)
+                    .in_set(SimulationSet),
+            );
+    }
+}
+
+#[derive(Event, Clone, Copy, Debug)]
+pub struct SpawnCorpseEvent {
+    pub position: Vec2,
+    pub energy: f32,
+    pub radius: f32,
+}
+
+fn corpses_spawn_action(
+    mut commands: Commands,
+    mut events: EventReader<SpawnCorpseEvent>,
+    handles: Res<SharedHandles>,
+) {
+    for ev in events.read() {
+        let corpse = Corpse {
+            energy: ev.energy,
+            decomposed: 0.,
+            radius: ev.radius * 0.7,
+        };
+        spawn_corpse(&mut commands, &handles, ev.position, corpse);
+    }
+}
+
+fn spawn_corpse(commands: &mut Commands, handles: &SharedHandles, position: Vec2, corpse: Corpse) {
+    let radius = corpse.radius;
+    commands.spawn((
+        MaterialMesh2dBundle {
+            mesh: handles.unit_circle.clone().into(),
+            material: handles.corpse_material.clone(),
+            // Below living cells, above the board.
+            transform: Transform::from_xyz(position.x, position.y, 0.5)
+                .with_scale(Vec3::new(radius, radius, 1.)),
+            ..default()
+        },
+        corpse,
+    ))
+        .insert((Collider::ball(radius), ColliderScale::Absolute(Vec2::ONE)))
+        .insert(Sensor)
+        .insert(CORPSE_COLLISION_GROUPS)
+        .insert(ActiveEvents::COLLISION_EVENTS);
+}
+
+/// Predators touching a corpse eat all of its remaining energy.
+fn scavenging_system(
+    mut commands: Commands,
+    mut collision_events: EventReader<CollisionEvent>,
+    mut predators: Query<(&mut Cell, &CellId), With<Predator>>,
+    corpses: Query<&Corpse>,
+    mut ate_events: EventWriter<CellAteEvent>,
+) {
+    // The predator with the lowest id gets a corpse that several reach in the same tick.
+    let mut meals: Vec<(CellId, f32, Entity, Entity)> = started_pairs(&mut collision_events)
+        .filter_map(|(scavenger, remains)| {
+            Some((*predators.get(scavenger).ok()?.1, corpses.get(remains).ok()?.energy, scavenger, remains))
+        })
+        .collect();
+    meals.sort_by(|a, b| a.0.cmp(&b.0).then(a.1.total_cmp(&b.1)));
+
+    let mut eaten = HashSet::new();
+    for (id, energy, scavenger, remains) in meals {
+        if eaten.insert(remains) {
+            predators.get_mut(scavenger).unwrap().0.energy += energy;
+            commands.entity(remains).despawn();
+            ate_events.send(CellAteEvent { id, food: Food::Corpse });
+        }
+    }
+}
+
+/// Corpses lose `corpse_decay_rate` energy per tick, which also enriches the
+/// ground below; every `seed_energy` worth of decomposed matter drops a seed
+/// next to the corpse.
+pub(crate) fn corpses_decay_action(
+    mut commands: Commands,
+    mut corpses: Query<(&Transform, &mut Corpse, Entity)>,
+    mut fields: Query<&mut ScalarFields>,
+    mut seed_events: EventWriter<SpawnSeedEvent>,
+    config: Res<SimulationConfig>,
+    mut rng: ResMut<SimulationRng>,
+) {
+    for (transform, mut corpse, entity) in corpses.iter_mut() {
+        let decay = config.corpse_decay_rate.min(corpse.energy.max(0.));
+        corpse.energy -= decay;
+        corpse.decomposed += decay;
+        // Only the fields of the board under the corpse cover its position.
+        for mut fields in fields.iter_mut() {
+            if let Some(layer) = fields.layer(NUTRIENTS) {
+                fields.deposit(layer, transform.translation.truncate(), decay);
+            }
+        }
+
+        while config.seed_energy > 0. && corpse.decomposed >= config.seed_energy {
+            corpse.decomposed -= config.seed_energy;
+            let offset = Vec2::from_angle(rng.0.gen_range(0. ..std::f32::consts::TAU))
+                * rng.0.gen_range(corpse.radius..=corpse.radius * 2.);
+            seed_events.send(SpawnSeedEvent {
+                position: transform.translation.truncate() + offset,
+            });
+        }
+
+        if corpse.energy <= 0. {
+            commands.entity(entity).despawn();
+        }
+    }
+}
@@ -0,0 +1,200 @@
+use std::ops::RangeInclusive;
+
+use bevy::{
+    input::{mouse::MouseButtonInput, ButtonState},
+    prelude::*,
+    window::Window,
+};
+use bevy_egui::EguiContexts;
+
+use crate::{
+    cameras::main_camera::*,
+    core::replay::UserInput,
+    entities::terrain::*,
+};
+
+/// Turns mouse clicks on the board into simulation requests.
+pub struct InputActionsPlugin;
+
+impl Plugin for InputActionsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<WorldEditor>()
+            .add_systems(Update, (mouse_spawn_cell_action, mouse_edit_world_action));
+    }
+}
+
+/// What a click on the board does.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum BoardTool {
+    #[default]
+    SpawnCells,
+    Rectangle,
+    Circle,
+    /// Left clicks add points, a right click finishes the wall.
+    Polyline,
+    Terrain,
+    Erase,
+}
+
+/// Costs the editor gives new terrain regions.
+pub(crate) const MOVE_COST_RANGE: RangeInclusive<f32> = 0.2..=10.;
+
+#[derive(Resource)]
+pub(crate) struct WorldEditor {
+    pub tool: BoardTool,
+    /// Cost of the terrain regions drawn next.
+    pub move_cost: f32,
+    /// Width of the polyline walls drawn next.
+    pub wall_width: f32,
+    drag_start: Option<Vec2>,
+    polyline: Vec<Vec2>,
+}
+
+impl Default for WorldEditor {
+    fn default() -> Self {
+        Self {
+            tool: BoardTool::SpawnCells,
+            move_cost: 3.,
+            wall_width: 6.,
+            drag_start: None,
+            polyline: Vec::new(),
+        }
+    }
+}
+
+impl WorldEditor {
+    pub fn cancel(&mut self) {
+        self.drag_start = None;
+        self.polyline.clear();
+    }
+}
+
+fn cursor_world_position(
+    windows: &Query<&Window>,
+    camera_q: &Query<(&Camera, &GlobalTransform), With<GameCapCamera>>,
+) -> Option<Vec2> {
+    let (camera, camera_transform) = camera_q.single();
+    windows
+        .single()
+        .cursor_position()
+        .and_then(|cursor| camera.viewport_to_world(camera_transform, cursor))
+        .map(|ray| ray.origin.truncate())
+}
+
+fn mouse_spawn_cell_action(
+    mut events: EventReader<MouseButtonInput>,
+    mut inputs: EventWriter<UserInput>,
+    mut contexts: EguiContexts,
+    editor: Res<WorldEditor>,
+    keys: Res<ButtonInput<KeyCode>>,
+    windows: Query<&Window>,
+    camera_q: Query<(&Camera, &GlobalTransform), With<GameCapCamera>>,
+) {
+    if editor.tool != BoardTool::SpawnCells || contexts.ctx_mut().is_pointer_over_area() {
+        events.clear();
+        return;
+    }
+    for ev in events.read() {
+        match ev.state {
+            ButtonState::Pressed => {
+                if let Some(world_position) = cursor_world_position(&windows, &camera_q) {
+                    // Shift+click places a predator instead of a cell.
+                    inputs.send(UserInput::SpawnCell {
+                        position: world_position.to_array(),
+                        predator: keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]),
+                    });
+                }
+            }
+            ButtonState::Released => {}
+        }
+    }
+}
+
+/// Draws obstacles and terrain into `SimulationConfig::world` with the selected tool.
+#[allow(clippy::too_many_arguments)]
+fn mouse_edit_world_action(
+    mut editor: ResMut<WorldEditor>,
+    mut inputs: EventWriter<UserInput>,
+    mut contexts: EguiContexts,
+    mut gizmos: Gizmos,
+    buttons: Res<ButtonInput<MouseButton>>,
+    keys: Res<ButtonInput<KeyCode>>,
+    windows: Query<&Window>,
+    camera_q: Query<(&Camera, &GlobalTransform), With<GameCapCamera>>,
+) {
+    if editor.tool == BoardTool::SpawnCells || keys.just_pressed(KeyCode::Escape) {
+        editor.cancel();
+        return;
+    }
+    let Some(cursor) = cursor_world_position(&windows, &camera_q) else { return };
+    let over_ui = contexts.ctx_mut().is_pointer_over_area();
+    let pressed = buttons.just_pressed(MouseButton::Left) && !over_ui;
+    let released = buttons.just_released(MouseButton::Left);
+
+    match editor.tool {
+        BoardTool::SpawnCells => {}
+        BoardTool::Rectangle | BoardTool::Circle | BoardTool::Terrain => {
+            if pressed {
+                editor.drag_start = Some(cursor);
+            }
+            let Some(start) = editor.drag_start else { return };
+            let rect = Rect::from_corners(start, cursor);
+            let radius = start.distance(cursor);
+            if editor.tool == BoardTool::Circle {
+                gizmos.circle_2d(start, radius, Color::WHITE);
+            } else {
+                gizmos.rect_2d(rect.center(), 0., rect.size(), Color::WHITE);
+            }
+            if !released {
+                return;
+            }
+            editor.drag_start = None;
+            let too_small = if editor.tool == BoardTool::Circle {
+                radius < 1.
+            } else {
+                rect.width() < 1. || rect.height() < 1.
+            };
+            if too_small {
+                return;
+            }
+            let (min, max) = (rect.min.to_array(), rect.max.to_array());
+            inputs.send(match editor.tool {
+                BoardTool::Rectangle => UserInput::AddObstacle(ObstacleShape::Rectangle { min, max }),
+                BoardTool::Circle => UserInput::AddObstacle(ObstacleShape::Circle {
+                    center: start.to_array(),
+                    radius,
+                }),
+                _ => UserInput::AddTerrain(TerrainRegion {
+                    min,
+                    max,
+                    move_cost: editor.move_cost.clamp(*MOVE_COST_RANGE.start(), *MOVE_COST_RANGE.end()),
+                }),
+            });
+        }
+        BoardTool::Polyline => {
+            if pressed {
+                editor.polyline.push(cursor);
+            }
+            if let Some(last) = editor.polyline.last() {
+                gizmos.linestrip_2d(editor.polyline.iter().copied(), Color::WHITE);
+                gizmos.line_2d(*last, cursor, Color::GRAY);
+            }
+            if buttons.just_pressed(MouseButton::Right) && !over_ui {
+                let points: Vec<[f32; 2]> = editor.polyline.drain(..).map(|point| point.to_array()).collect();
+                if points.len() >= 2 {
+                    inputs.send(UserInput::AddObstacle(ObstacleShape::Polyline {
+                        points,
+                        width: editor.wall_width,
+                    }));
+                }
+            }
+        }
+        BoardTool::Erase => {
+            if pressed {
+                inputs.send(UserInput::Erase {
+                    position: cursor.to_array(),
+                });
+            }
+        }
+    }
+}
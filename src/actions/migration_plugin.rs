@@ -0,0 +1,60 @@
+use bevy::{math::Vec3Swizzles, prelude::*};
+use rand::seq::SliceRandom;
+
+use crate::{
+    actions::cells_plugin::{cells_sense_system, random_open_position},
+    core::{
+        config::SimulationConfig,
+        simulation::{SimulationRng, SimulationSet, SimulationTick},
+    },
+    entities::{cells::*, world_board::*},
+};
+
+/// Carries cells between boards along `SimulationConfig::migrations`.
+pub struct MigrationPlugin;
+
+impl Plugin for MigrationPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, migration_action.before(cells_sense_system).in_set(SimulationSet));
+    }
+}
+
+/// Every `interval` ticks of a route, moves up to `count` random cells of its
+/// `from` board to open ground on its `to` board.
+fn migration_action(
+    mut cells: Query<(&mut Transform, &CellId, Entity), With<Cell>>,
+    boards: Query<&WorldBoard>,
+    config: Res<SimulationConfig>,
+    tick: Res<SimulationTick>,
+    mut rng: ResMut<SimulationRng>,
+) {
+    for route in &config.migrations {
+        if route.interval == 0 || tick.0 == 0 || tick.0 % route.interval as u64 != 0 {
+            continue;
+        }
+        let find = |name: &str| boards.iter().find(|board| board.settings.name == name);
+        let (Some(from), Some(to)) = (find(&route.from), find(&route.to)) else {
+            warn!("Migration from `{}` to `{}` skipped: no such board", route.from, route.to);
+            continue;
+        };
+
+        // Sorted by id, so the pick only depends on the random source.
+        let mut candidates: Vec<(CellId, Entity)> = cells
+            .iter()
+            .filter(|(transform, ..)| from.contains(transform.translation.xy()))
+            .map(|(_, &id, entity)| (id, entity))
+            .collect();
+        candidates.sort_by_key(|(id, _)| *id);
+        let migrants: Vec<Entity> = candidates
+            .choose_multiple(&mut rng.0, route.count as usize)
+            .map(|(_, entity)| *entity)
+            .collect();
+
+        for entity in migrants {
+            let position = random_open_position(to, &config.world, &mut rng);
+            if let Ok((mut transform, ..)) = cells.get_mut(entity) {
+                transform.translation = position.extend(transform.translation.z);
+            }
+        }
+    }
+}
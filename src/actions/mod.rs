@@ -1,3 +1,8 @@
-pub(crate) mod cells_plugin;
-pub(crate) mod board_plugin;
-pub(crate) mod seeds_plugin;
\ No newline at end of file
+pub mod cells_plugin;
+pub mod board_plugin;
+pub mod corpses_plugin;
+pub mod input_plugin;
+pub mod migration_plugin;
+pub mod plants_plugin;
+pub mod seeds_plugin;
+pub mod terrain_plugin;
\ No newline at end of file
@@ -0,0 +1,126 @@
+use bevy::{math::Vec3Swizzles, prelude::*, sprite::MaterialMesh2dBundle};
+use rand::Rng;
+
+use crate::{
+    actions::{cells_plugin::cells_initial_spawn_action, corpses_plugin::corpses_decay_action, seeds_plugin::SpawnSeedEvent},
+    core::{
+        clock::WorldClock,
+        config::SimulationConfig,
+        fields::{ScalarFields, NUTRIENTS},
+        light::LightField,
+        shared_handles::SharedHandles,
+        simulation::{SimulationRng, SimulationSet},
+    },
+    entities::{plant::*, world_board::*},
+};
+
+/// Share of the maximum size a plant starts with, so it stays visible.
+const MIN_PLANT_SCALE: f32 = 0.25;
+
+pub struct PlantsPlugin;
+
+impl Plugin for PlantsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(PostStartup, plants_spawn_action.after(cells_initial_spawn_action))
+            .add_systems(
+                Update,
+                (plants_grow_action, plants_seed_action)
+                    .chain()
+                    .after(corpses_decay_action)
+                    .in_set(SimulationSet),
+            );
+    }
+}
+
+pub(crate) fn plants_spawn_action(
+    mut commands: Commands,
+    handles: Res<SharedHandles>,
+    boards: Query<&WorldBoard>,
+    config: Res<SimulationConfig>,
+    mut rng: ResMut<SimulationRng>,
+) {
+    for board in boards.iter() {
+        let board_rect = board.settings.rect;
+        for _ in 0..board.settings.initial_plants {
+            let position = Vec2::new(
+                rng.0.gen_range(board_rect.min.x..board_rect.max.x),
+                rng.0.gen_range(board_rect.min.y..board_rect.max.y),
+            );
+            if !board.contains(position) || config.world.blocks(position, 0.) {
+                continue;
+            }
+            let plant = Plant {
+                energy: 0.,
+                seed_progress: 0.,
+            };
+            spawn_plant(&mut commands, &handles, position, plant, config.plant_max_radius * MIN_PLANT_SCALE);
+        }
+    }
+}
+
+/// Spawns a plant drawn `radius` wide, the unit hexagon scaled by its transform.
+fn spawn_plant(commands: &mut Commands, handles: &SharedHandles, position: Vec2, plant: Plant, radius: f32) {
+    commands.spawn((
+        MaterialMesh2dBundle {
+            mesh: handles.unit_hexagon.clone().into(),
+            material: handles.plant_material.clone(),
+            // Under cells and seeds.
+            transform: Transform::from_xyz(position.x, position.y, 0.5).with_scale(Vec3::new(radius, radius, 1.)),
+            ..default()
+        },
+        plant,
+    ));
+}
+
+/// Plants store the light and nutrients they get and grow with their stored energy.
+fn plants_grow_action(
+    mut plants: Query<(&mut Transform, &mut Plant)>,
+    mut boards: Query<(&WorldBoard, &mut ScalarFields)>,
+    light: Res<LightField>,
+    config: Res<SimulationConfig>,
+) {
+    let mut boards: Vec<_> = boards.iter_mut().collect();
+    for (mut transform, mut plant) in plants.iter_mut() {
+        let position = transform.translation.xy();
+        let Some(index) = board_index_at(boards.iter().map(|(board, _)| *board), position) else { continue };
+        let (board, fields) = &mut boards[index];
+        let intensity = light.intensity_at(position, board.settings.rect);
+        let room = (config.plant_max_energy - plant.energy).max(0.);
+        let uptake = fields.layer(NUTRIENTS).map_or(0., |layer| {
+            fields.take(layer, position, config.plant_nutrient_uptake.min(room))
+        });
+        plant.energy = (plant.energy + intensity * config.plant_photosynthesis_rate + uptake)
+            .min(config.plant_max_energy);
+
+        let fullness = (plant.energy / config.plant_max_energy).clamp(0., 1.).sqrt();
+        let radius = config.plant_max_radius * (MIN_PLANT_SCALE + (1. - MIN_PLANT_SCALE) * fullness);
+        transform.scale = Vec3::new(radius, radius, 1.);
+    }
+}
+
+/// Every `plant_seed_interval` ticks, stretched or shortened by the season, a
+/// plant with enough energy drops a seed within twice its size.
+pub(crate) fn plants_seed_action(
+    mut plants: Query<(&Transform, &mut Plant)>,
+    mut seed_events: EventWriter<SpawnSeedEvent>,
+    config: Res<SimulationConfig>,
+    clock: Res<WorldClock>,
+    mut rng: ResMut<SimulationRng>,
+) {
+    for (transform, mut plant) in plants.iter_mut() {
+        plant.seed_progress = (plant.seed_progress + clock.season.growth())
+            .min(config.plant_seed_interval as f32);
+        if plant.seed_progress < config.plant_seed_interval as f32 || plant.energy < config.seed_energy {
+            continue;
+        }
+
+        plant.seed_progress = 0.;
+        plant.energy -= config.seed_energy;
+        let reach = transform.scale.x;
+        let offset = Vec2::from_angle(rng.0.gen_range(0. ..std::f32::consts::TAU))
+            * rng.0.gen_range(reach..=reach * 2. + 1.);
+        seed_events.send(SpawnSeedEvent {
+            position: transform.translation.xy() + offset,
+        });
+    }
+}
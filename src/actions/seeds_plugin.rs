@@ -1,69 +1,150 @@
 use bevy::{prelude::*, sprite::MaterialMesh2dBundle};
-use bevy_rapier2d::dynamics::{RigidBody};
+use bevy_rapier2d::dynamics::{Damping, RigidBody};
 use bevy_rapier2d::geometry::{ActiveEvents, Collider};
 use rand::prelude::*;
 
-use crate::entities::{seed::*, world_board::*};
-
-const CELLS_STEP: i32 = 32;
+use crate::{
+    actions::plants_plugin::plants_seed_action,
+    core::{config::SimulationConfig, shared_handles::*, simulation::{SimulationRng, SimulationSet}},
+    entities::{collision_groups::SEED_COLLISION_GROUPS, seed::*, world_board::*},
+};
 
 pub struct SeedsPlugin;
 
 impl Plugin for SeedsPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(PostStartup, (seeds_spawn_action));
+        app.add_event::<SpawnSeedEvent>()
+            .add_systems(PostStartup, seeds_spawn_action)
+            .add_systems(
+                Update,
+                (
+                    seeds_spawn_event_action.after(plants_seed_action),
+                    seeds_damping_action.run_if(resource_changed::<SimulationConfig>),
+                )
+                    .in_set(SimulationSet),
+            );
     }
 }
 
-fn seeds_spawn_action(
+/// Asks for a single seed at a world position; positions off the walkable board or inside obstacles are ignored.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct SpawnSeedEvent {
+    pub position: Vec2,
+}
+
+pub(crate) fn seeds_spawn_action(
     mut commands: Commands,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<ColorMaterial>>,
+    handles: Res<SharedHandles>,
     boards: Query<&WorldBoard>,
-    time: Res<Time>,
+    config: Res<SimulationConfig>,
+    mut rng: ResMut<SimulationRng>,
 ) {
-    let board = boards.single();
-    let min_x: i32 = board.settings.rect.min.x as i32 + 3;
-    let min_y: i32 = board.settings.rect.min.y as i32 + 3;
-    let max_x: i32 = board.settings.rect.max.x as i32 - 3;
-    let max_y: i32 = board.settings.rect.max.y as i32 - 3;
+    for board in boards.iter() {
+        if !board.settings.seeds_grid {
+            continue;
+        }
+        let min_x: i32 = board.settings.rect.min.x as i32 + 3;
+        let min_y: i32 = board.settings.rect.min.y as i32 + 3;
+        let max_x: i32 = board.settings.rect.max.x as i32 - 3;
+        let max_y: i32 = board.settings.rect.max.y as i32 - 3;
+
+        let step = (board.settings.seeds_step as i32).max(1);
 
-    let mut rng = thread_rng();
-    for x in (min_x..max_x).step_by(32) {
-        for y in (min_y..max_y).step_by(32) {
-            let pos_x: f32 = if x + CELLS_STEP < max_x {
-                rng.gen_range(x..=x + CELLS_STEP)
-            } else {
-                rng.gen_range(x..=max_x)
-            } as f32;
+        // With a board map the grid's seed count is spread by fertility instead:
+        // each candidate position is kept with the fertility under it.
+        if let Some(map) = &board.settings.map {
+            // No room for a seed off the edges, where the grid would not place one either.
+            if min_x > max_x || min_y > max_y {
+                continue;
+            }
+            let candidates = ((max_x - min_x) / step + 1) * ((max_y - min_y) / step + 1);
+            for _ in 0..candidates.max(0) {
+                let position = Vec2::new(
+                    rng.0.gen_range(min_x..=max_x) as f32,
+                    rng.0.gen_range(min_y..=max_y) as f32,
+                );
+                if rng.0.gen::<f32>() < map.fertility_at(position, board.settings.rect)
+                    && !config.world.blocks(position, 0.)
+                {
+                    spawn_seed(&mut commands, &handles, position, config.seed_damping);
+                }
+            }
+            continue;
+        }
+
+        for x in (min_x..max_x).step_by(step as usize) {
+            for y in (min_y..max_y).step_by(step as usize) {
+                let pos_x: f32 = if x + step < max_x {
+                    rng.0.gen_range(x..=x + step)
+                } else {
+                    rng.0.gen_range(x..=max_x)
+                } as f32;
 
-            let pos_y: f32 = if y + CELLS_STEP < max_y {
-                rng.gen_range(y..=y + CELLS_STEP)
-            } else {
-                rng.gen_range(y..=max_y)
-            } as f32;
+                let pos_y: f32 = if y + step < max_y {
+                    rng.0.gen_range(y..=y + step)
+                } else {
+                    rng.0.gen_range(y..=max_y)
+                } as f32;
 
-            commands.spawn((
-                MaterialMesh2dBundle {
-                    mesh: meshes.add(Rectangle::from_size(Vec2 { x: 3., y: 3. })).into(),
-                    material: materials.add(ColorMaterial::from(Color::YELLOW)),
-                    transform: Transform::from_xyz(pos_x, pos_y, 1.),
-                    ..default()
-                },
-                Seed {},
-            ))
-                .insert(RigidBody::Dynamic)
-                .insert(Collider::cuboid(3. / 2., 3. / 2.))
-                .insert(ActiveEvents::COLLISION_EVENTS);
+                let position = Vec2::new(pos_x, pos_y);
+                if !config.world.blocks(position, 0.) {
+                    spawn_seed(&mut commands, &handles, position, config.seed_damping);
+                }
 
-            // commands.spawn((
-            //     SceneBundle {
-            //         scene: assetServer.load("models\\seed.obj"),
-            //         transform: Transform::from_xyz(pos_x, pos_y, 1.),
-            //         ..default()
-            //     },
-            //     Seed {},
-            // ));
+                // commands.spawn((
+                //     SceneBundle {
+                //         scene: assetServer.load("models\\seed.obj"),
+                //         transform: Transform::from_xyz(pos_x, pos_y, 1.),
+                //         ..default()
+                //     },
+                //     Seed {},
+                // ));
+            }
         }
     }
 }
+
+fn seeds_spawn_event_action(
+    mut commands: Commands,
+    mut events: EventReader<SpawnSeedEvent>,
+    handles: Res<SharedHandles>,
+    boards: Query<&WorldBoard>,
+    config: Res<SimulationConfig>,
+) {
+    for ev in events.read() {
+        let on_board = boards.iter().any(|board| board.contains(ev.position));
+        if on_board && !config.world.blocks(ev.position, 0.) {
+            spawn_seed(&mut commands, &handles, ev.position, config.seed_damping);
+        }
+    }
+}
+
+/// Gives the seeds already out there a changed `SimulationConfig::seed_damping`.
+fn seeds_damping_action(config: Res<SimulationConfig>, mut seeds: Query<&mut Damping, With<Seed>>) {
+    let damping = Damping { linear_damping: config.seed_damping, angular_damping: config.seed_damping };
+    for mut seed in seeds.iter_mut() {
+        seed.set_if_neq(damping);
+    }
+}
+
+fn spawn_seed(
+    commands: &mut Commands,
+    handles: &SharedHandles,
+    position: Vec2,
+    damping: f32,
+) {
+    commands.spawn((
+        MaterialMesh2dBundle {
+            mesh: handles.seed_mesh.clone().into(),
+            material: handles.seed_material.clone(),
+            transform: Transform::from_xyz(position.x, position.y, 1.),
+            ..default()
+        },
+        Seed {},
+    ))
+        .insert(RigidBody::Dynamic)
+        .insert(Damping { linear_damping: damping, angular_damping: damping })
+        .insert(Collider::cuboid(SEED_SIZE / 2., SEED_SIZE / 2.))
+        .insert(SEED_COLLISION_GROUPS)
+        .insert(ActiveEvents::COLLISION_EVENTS);
+}
\ No newline at end of file
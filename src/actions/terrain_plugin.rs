@@ -0,0 +1,147 @@
+use bevy::{prelude::*, sprite::MaterialMesh2dBundle};
+use bevy_rapier2d::dynamics::RigidBody;
+use bevy_rapier2d::geometry::Collider;
+
+use crate::{
+    core::{
+        config::SimulationConfig,
+        simulation::{LaidOut, SimulationSet},
+    },
+    entities::{collision_groups::OBSTACLE_COLLISION_GROUPS, terrain::*},
+};
+
+const OBSTACLE_COLOR: Color = Color::rgb(0.45, 0.45, 0.5);
+
+pub struct TerrainPlugin;
+
+impl Plugin for TerrainPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SpawnedWorld>()
+            .add_systems(
+                First,
+                terrain_sync_action
+                    .run_if(resource_changed::<SimulationConfig>)
+                    .in_set(SimulationSet),
+            );
+    }
+}
+
+/// The layout the obstacle and terrain entities were last spawned from.
+#[derive(Resource, Default)]
+pub(crate) struct SpawnedWorld(Option<WorldLayout>);
+
+/// Respawns obstacle and terrain entities whenever `SimulationConfig::world` differs from what is on the board.
+pub(crate) fn terrain_sync_action(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    config: Res<SimulationConfig>,
+    mut spawned: ResMut<SpawnedWorld>,
+    existing: Query<Entity, LaidOut>,
+) {
+    if spawned.0.as_ref() == Some(&config.world) {
+        return;
+    }
+    for entity in existing.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    for region in &config.world.terrain {
+        let rect = region.rect();
+        // Darker the more expensive, lighter for regions easier than open ground.
+        let color = if region.move_cost >= 1. {
+            Color::rgba(0.35, 0.25, 0.1, (0.15 * region.move_cost).min(0.7))
+        } else {
+            Color::rgba(0.6, 0.9, 1., 0.3)
+        };
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color,
+                    custom_size: Some(rect.size()),
+                    ..default()
+                },
+                // Over the board and field overlay, under plants and cells.
+                transform: Transform::from_translation(rect.center().extend(0.3)),
+                ..default()
+            },
+            TerrainPatch,
+        ));
+    }
+
+    let material = materials.add(ColorMaterial::from(OBSTACLE_COLOR));
+    for obstacle in &config.world.obstacles {
+        match obstacle {
+            ObstacleShape::Rectangle { min, max } => {
+                let rect = Rect::from_corners(Vec2::from(*min), Vec2::from(*max));
+                commands.spawn((
+                    MaterialMesh2dBundle {
+                        mesh: meshes.add(Rectangle::from_size(rect.size())).into(),
+                        material: material.clone(),
+                        transform: Transform::from_translation(rect.center().extend(0.4)),
+                        ..default()
+                    },
+                    Obstacle,
+                    RigidBody::Fixed,
+                    Collider::cuboid(rect.half_size().x, rect.half_size().y),
+                    OBSTACLE_COLLISION_GROUPS,
+                ));
+            }
+            ObstacleShape::Circle { center, radius } => {
+                commands.spawn((
+                    MaterialMesh2dBundle {
+                        mesh: meshes.add(Circle { radius: *radius }).into(),
+                        material: material.clone(),
+                        transform: Transform::from_xyz(center[0], center[1], 0.4),
+                        ..default()
+                    },
+                    Obstacle,
+                    RigidBody::Fixed,
+                    Collider::ball(*radius),
+                    OBSTACLE_COLLISION_GROUPS,
+                ));
+            }
+            ObstacleShape::Polyline { points, width } => {
+                let radius = width / 2.;
+                let segments: Vec<(Vec2, Vec2)> = points
+                    .windows(2)
+                    .map(|segment| (Vec2::from(segment[0]), Vec2::from(segment[1])))
+                    .collect();
+                if segments.is_empty() {
+                    continue;
+                }
+                let collider = Collider::compound(
+                    segments
+                        .iter()
+                        .map(|&(start, end)| (Vec2::ZERO, 0., Collider::capsule(start, end, radius)))
+                        .collect(),
+                );
+                commands
+                    .spawn((
+                        SpatialBundle::default(),
+                        Obstacle,
+                        RigidBody::Fixed,
+                        collider,
+                        OBSTACLE_COLLISION_GROUPS,
+                    ))
+                    .with_children(|parent| {
+                        // Capsule meshes stand along the Y axis.
+                        for &(start, end) in &segments {
+                            let along = end - start;
+                            parent.spawn(MaterialMesh2dBundle {
+                                mesh: meshes.add(Capsule2d::new(radius, along.length())).into(),
+                                material: material.clone(),
+                                transform: Transform::from_translation(((start + end) / 2.).extend(0.4))
+                                    .with_rotation(Quat::from_rotation_z(
+                                        along.y.atan2(along.x) - std::f32::consts::FRAC_PI_2,
+                                    )),
+                                ..default()
+                            });
+                        }
+                    });
+            }
+        }
+    }
+
+    spawned.0 = Some(config.world.clone());
+}
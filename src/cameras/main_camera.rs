@@ -12,7 +12,7 @@ pub struct GameCapCameraPlugin;
 impl Plugin for GameCapCameraPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(Startup, setup_camera)
-            .add_plugins(PanCamPlugin::default());
+            .add_plugins(PanCamPlugin);
     }
 }
 
@@ -1 +1 @@
-pub(crate) mod main_camera;
\ No newline at end of file
+pub mod main_camera;
\ No newline at end of file
@@ -0,0 +1,141 @@
+use std::f32::consts::TAU;
+
+use bevy::prelude::*;
+
+use crate::core::{config::SimulationConfig, light::LightField, simulation::{SimulationSet, SimulationTick}};
+
+pub struct ClockPlugin;
+
+impl Plugin for ClockPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<WorldClock>()
+            .add_systems(First, update_clock_system.in_set(SimulationSet));
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Season {
+    Spring,
+    Summer,
+    Autumn,
+    Winter,
+}
+
+impl Season {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Season::Spring => "Spring",
+            Season::Summer => "Summer",
+            Season::Autumn => "Autumn",
+            Season::Winter => "Winter",
+        }
+    }
+
+    /// Share of the summer sunlight.
+    pub fn light(&self) -> f32 {
+        match self {
+            Season::Spring => 0.85,
+            Season::Summer => 1.,
+            Season::Autumn => 0.7,
+            Season::Winter => 0.45,
+        }
+    }
+
+    /// How fast plants produce seeds.
+    pub fn growth(&self) -> f32 {
+        match self {
+            Season::Spring => 1.3,
+            Season::Summer => 1.,
+            Season::Autumn => 0.7,
+            Season::Winter => 0.3,
+        }
+    }
+
+    /// How much energy cells burn per tick.
+    pub fn metabolism(&self) -> f32 {
+        match self {
+            Season::Spring => 1.,
+            Season::Summer => 1.1,
+            Season::Autumn => 1.,
+            Season::Winter => 1.25,
+        }
+    }
+
+    pub fn tint(&self) -> Color {
+        match self {
+            Season::Spring => Color::rgb(0.8, 1., 0.8),
+            Season::Summer => Color::rgb(1., 0.95, 0.8),
+            Season::Autumn => Color::rgb(1., 0.8, 0.6),
+            Season::Winter => Color::rgb(0.85, 0.9, 1.),
+        }
+    }
+}
+
+/// Time of day and season, derived from the tick every frame.
+#[derive(Resource, Clone, Copy, Debug)]
+pub struct WorldClock {
+    pub day: u64,
+    /// Position within the current day, `0` is midnight and `0.5` noon.
+    pub day_phase: f32,
+    pub season: Season,
+    /// Sunlight in `night_light..=1`, before the season is applied.
+    pub daylight: f32,
+}
+
+impl Default for WorldClock {
+    fn default() -> Self {
+        Self {
+            day: 0,
+            day_phase: 0.5,
+            season: Season::Spring,
+            daylight: 1.,
+        }
+    }
+}
+
+impl WorldClock {
+    pub fn at(tick: u64, config: &SimulationConfig) -> Self {
+        if config.day_length == 0 {
+            return Self::default();
+        }
+
+        let day_length = config.day_length as u64;
+        let day = tick / day_length;
+        // Runs start at dawn rather than midnight.
+        let day_phase = ((tick % day_length) as f32 / day_length as f32 + 0.25) % 1.;
+        let season = match (day / config.days_per_season.max(1) as u64) % 4 {
+            0 => Season::Spring,
+            1 => Season::Summer,
+            2 => Season::Autumn,
+            _ => Season::Winter,
+        };
+        let sun = (0.5 - 0.5 * (day_phase * TAU).cos()).clamp(0., 1.);
+        let daylight = config.night_light + (1. - config.night_light) * sun;
+
+        Self {
+            day,
+            day_phase,
+            season,
+            daylight,
+        }
+    }
+
+    pub fn light(&self) -> f32 {
+        self.daylight * self.season.light()
+    }
+
+    pub fn hours_minutes(&self) -> (u32, u32) {
+        let minutes = (self.day_phase * 24. * 60.) as u32;
+        (minutes / 60, minutes % 60)
+    }
+}
+
+fn update_clock_system(
+    tick: Res<SimulationTick>,
+    config: Res<SimulationConfig>,
+    mut clock: ResMut<WorldClock>,
+    mut light: ResMut<LightField>,
+) {
+    *clock = WorldClock::at(tick.0, &config);
+    light.brightness = clock.light();
+}
@@ -0,0 +1,431 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    core::{
+        fields::{default_field_layers, FieldLayerSettings},
+        network::BrainBackend,
+    },
+    entities::{genome::Genome, terrain::WorldLayout},
+};
+
+#[derive(Resource, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SimulationConfig {
+    pub rng_seed: u64,
+    pub board_width: f32,
+    pub board_height: f32,
+    /// PNG stretched over the board: alpha marks the walkable area, green the seed fertility.
+    pub board_image: Option<String>,
+    pub seeds_step: f32,
+    pub seed_energy: f32,
+    /// Linear and angular damping of seeds; without gravity nothing else stops a pushed seed.
+    pub seed_damping: f32,
+    pub initial_cells: u32,
+    pub cell_radius: f32,
+    pub cell_initial_energy: f32,
+    pub cell_move_cost: f32,
+    pub cell_division_energy: f32,
+    pub cell_speed: f32,
+    pub cell_sensor_range: f32,
+    pub mutation_rate: f32,
+    pub species_threshold: f32,
+    pub initial_predators: u32,
+    pub predator_attack_cost: f32,
+    /// Chance that an attack on a smaller or weaker cell succeeds.
+    pub predator_success_chance: f32,
+    /// Prey counts as smaller when its radius is below this share of the predator's.
+    pub predator_size_ratio: f32,
+    /// Share of the prey's energy the predator gets; the rest is left in the corpse.
+    pub predator_energy_gain: f32,
+    /// Energy a corpse holds on top of what the cell had left, for a founder-sized
+    /// cell. Prey leaves none, as its body was eaten.
+    pub corpse_body_energy: f32,
+    /// Energy per tick a corpse loses to decomposition.
+    pub corpse_decay_rate: f32,
+    /// Whether seeds are scattered over the board at start, besides what plants drop.
+    pub seeds_grid: bool,
+    pub initial_plants: u32,
+    /// Energy per tick a plant makes in full light.
+    pub plant_photosynthesis_rate: f32,
+    pub plant_max_energy: f32,
+    pub plant_max_radius: f32,
+    /// Ticks between two seeds dropped by a plant; a seed costs the plant `seed_energy`.
+    pub plant_seed_interval: u32,
+    /// Ticks per day; `0` keeps a permanent spring noon.
+    pub day_length: u32,
+    pub days_per_season: u32,
+    /// Share of the daylight left at midnight.
+    pub night_light: f32,
+    /// Side of a grid cell of the scalar fields laid over the board.
+    pub field_cell_size: f32,
+    pub field_layers: Vec<FieldLayerSettings>,
+    /// Pheromone a prey cell leaves behind per tick.
+    pub cell_pheromone_deposit: f32,
+    /// Nutrients per tick a plant can take up from the ground below it.
+    pub plant_nutrient_uptake: f32,
+    /// Obstacles and terrain regions, edited in the app and saved as the world file.
+    pub world: WorldLayout,
+    /// Islands of the world; when empty a single `main` board is made from the
+    /// `board_*`, `seeds_*` and `initial_*` fields above.
+    pub boards: Vec<BoardDefinition>,
+    pub migrations: Vec<MigrationRoute>,
+    pub brain_backend: BrainBackend,
+}
+
+/// One named board, with its own place in the world and food policy.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BoardDefinition {
+    pub name: String,
+    pub center: [f32; 2],
+    pub width: f32,
+    pub height: f32,
+    pub image: Option<String>,
+    pub seeds_grid: bool,
+    pub seeds_step: f32,
+    pub initial_cells: u32,
+    pub initial_predators: u32,
+    pub initial_plants: u32,
+}
+
+impl Default for BoardDefinition {
+    fn default() -> Self {
+        Self {
+            name: "main".to_string(),
+            center: [0., 0.],
+            width: 500.,
+            height: 500.,
+            image: None,
+            seeds_grid: true,
+            seeds_step: 32.,
+            initial_cells: 0,
+            initial_predators: 0,
+            initial_plants: 6,
+        }
+    }
+}
+
+impl BoardDefinition {
+    pub fn rect(&self) -> Rect {
+        Rect::from_center_size(Vec2::from(self.center), Vec2::new(self.width, self.height))
+    }
+}
+
+/// Every `interval` ticks, `count` cells of board `from` are carried to board `to`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MigrationRoute {
+    pub from: String,
+    pub to: String,
+    pub interval: u32,
+    pub count: u32,
+}
+
+impl Default for SimulationConfig {
+    fn default() -> Self {
+        Self {
+            rng_seed: rand::random(),
+            board_width: 500.,
+            board_height: 500.,
+            board_image: None,
+            seeds_step: 32.,
+            seed_energy: 10.,
+            seed_damping: 5.,
+            initial_cells: 0,
+            cell_radius: 15.,
+            cell_initial_energy: 50.,
+            cell_move_cost: 0.05,
+            cell_division_energy: 100.,
+            cell_speed: 1.,
+            cell_sensor_range: 100.,
+            mutation_rate: 0.05,
+            species_threshold: 0.15,
+            initial_predators: 0,
+            predator_attack_cost: 2.,
+            predator_success_chance: 0.8,
+            predator_size_ratio: 1.,
+            predator_energy_gain: 0.8,
+            corpse_body_energy: 20.,
+            corpse_decay_rate: 0.1,
+            seeds_grid: true,
+            initial_plants: 6,
+            plant_photosynthesis_rate: 0.5,
+            plant_max_energy: 100.,
+            plant_max_radius: 8.,
+            plant_seed_interval: 30,
+            day_length: 1200,
+            days_per_season: 3,
+            night_light: 0.15,
+            field_cell_size: 10.,
+            field_layers: default_field_layers(),
+            cell_pheromone_deposit: 0.5,
+            plant_nutrient_uptake: 0.05,
+            world: WorldLayout::default(),
+            boards: Vec::new(),
+            migrations: Vec::new(),
+            brain_backend: BrainBackend::Scalar,
+        }
+    }
+}
+
+impl SimulationConfig {
+    /// Crowded scene for measuring frame times: about 50k seeds and 10k cells.
+    pub fn benchmark() -> Self {
+        Self::benchmark_with_cells(10_000)
+    }
+
+    /// The benchmark scene with another population, on a board sized to keep
+    /// the same crowding as the 10k cell scene.
+    pub fn benchmark_with_cells(cells: u32) -> Self {
+        let side = 2240. * (cells as f32 / 10_000.).sqrt();
+        Self {
+            rng_seed: 0,
+            board_width: side,
+            board_height: side,
+            seeds_step: 10.,
+            initial_cells: cells,
+            // Small enough that the cells do not clear the seeds in the first ticks.
+            cell_radius: 4.,
+            initial_plants: 0,
+            ..default()
+        }
+    }
+
+    /// Genome given to cells that were not born from another cell.
+    pub fn founder_genome(&self) -> Genome {
+        Genome {
+            radius: self.cell_radius,
+            speed: self.cell_speed,
+            sensor_range: self.cell_sensor_range,
+        }
+    }
+
+    pub fn board_definitions(&self) -> Vec<BoardDefinition> {
+        if !self.boards.is_empty() {
+            return self.boards.clone();
+        }
+        vec![BoardDefinition {
+            name: "main".to_string(),
+            center: [0., 0.],
+            width: self.board_width,
+            height: self.board_height,
+            image: self.board_image.clone(),
+            seeds_grid: self.seeds_grid,
+            seeds_step: self.seeds_step,
+            initial_cells: self.initial_cells,
+            initial_predators: self.initial_predators,
+            initial_plants: self.initial_plants,
+        }]
+    }
+
+    /// Every name `set_parameter` takes, the field layers last.
+    pub fn parameter_names(&self) -> Vec<String> {
+        let layers = self.field_layers.iter().flat_map(|layer| {
+            ["diffusion", "decay"].map(|setting| format!("fields.{}.{setting}", layer.name))
+        });
+        PARAMETERS.iter().map(|name| name.to_string()).chain(layers).collect()
+    }
+
+    /// The value `set_parameter` sets, `None` for unknown names.
+    pub fn parameter(&self, name: &str) -> Option<f64> {
+        let value = match name {
+            "board.width" => self.board_width,
+            "board.height" => self.board_height,
+            "seeds.step" => self.seeds_step,
+            "seeds.energy" => self.seed_energy,
+            "seeds.damping" => self.seed_damping,
+            "cells.initial" => self.initial_cells as f32,
+            "cells.radius" => self.cell_radius,
+            "cells.initial_energy" => self.cell_initial_energy,
+            "cells.move_cost" => self.cell_move_cost,
+            "cells.division_energy" => self.cell_division_energy,
+            "cells.speed" => self.cell_speed,
+            "cells.sensor_range" => self.cell_sensor_range,
+            "cells.mutation_rate" => self.mutation_rate,
+            "species.threshold" => self.species_threshold,
+            "predators.initial" => self.initial_predators as f32,
+            "predators.attack_cost" => self.predator_attack_cost,
+            "predators.success_chance" => self.predator_success_chance,
+            "predators.size_ratio" => self.predator_size_ratio,
+            "predators.energy_gain" => self.predator_energy_gain,
+            "corpses.body_energy" => self.corpse_body_energy,
+            "corpses.decay_rate" => self.corpse_decay_rate,
+            "seeds.grid" => self.seeds_grid as u8 as f32,
+            "plants.initial" => self.initial_plants as f32,
+            "plants.photosynthesis_rate" => self.plant_photosynthesis_rate,
+            "plants.max_energy" => self.plant_max_energy,
+            "plants.max_radius" => self.plant_max_radius,
+            "plants.seed_interval" => self.plant_seed_interval as f32,
+            "clock.day_length" => self.day_length as f32,
+            "clock.days_per_season" => self.days_per_season as f32,
+            "clock.night_light" => self.night_light,
+            "fields.cell_size" => self.field_cell_size,
+            "cells.pheromone_deposit" => self.cell_pheromone_deposit,
+            "plants.nutrient_uptake" => self.plant_nutrient_uptake,
+            _ => {
+                let (layer_name, setting) = name.strip_prefix("fields.")?.rsplit_once('.')?;
+                let layer = self.field_layers.iter().find(|layer| layer.name == layer_name)?;
+                match setting {
+                    "diffusion" => layer.diffusion,
+                    "decay" => layer.decay,
+                    _ => return None,
+                }
+            }
+        };
+        Some(value as f64)
+    }
+
+    pub fn set_parameter(&mut self, name: &str, value: f64) -> Result<(), String> {
+        if !value.is_finite() {
+            return Err(format!("`{name}` must be a finite number, got {value}"));
+        }
+        match name {
+            "board.width" => self.board_width = positive(name, value)?,
+            "board.height" => self.board_height = positive(name, value)?,
+            "seeds.step" => self.seeds_step = positive(name, value)?,
+            "seeds.energy" => self.seed_energy = non_negative(name, value)?,
+            "seeds.damping" => self.seed_damping = non_negative(name, value)?,
+            "cells.initial" => self.initial_cells = whole(name, value, 0)?,
+            "cells.radius" => self.cell_radius = positive(name, value)?,
+            "cells.initial_energy" => self.cell_initial_energy = positive(name, value)?,
+            "cells.move_cost" => self.cell_move_cost = non_negative(name, value)?,
+            "cells.division_energy" => self.cell_division_energy = positive(name, value)?,
+            "cells.speed" => self.cell_speed = positive(name, value)?,
+            "cells.sensor_range" => self.cell_sensor_range = non_negative(name, value)?,
+            "cells.mutation_rate" => self.mutation_rate = within(name, value, 1.)?,
+            "species.threshold" => self.species_threshold = positive(name, value)?,
+            "predators.initial" => self.initial_predators = whole(name, value, 0)?,
+            "predators.attack_cost" => self.predator_attack_cost = non_negative(name, value)?,
+            "predators.success_chance" => self.predator_success_chance = within(name, value, 1.)?,
+            "predators.size_ratio" => self.predator_size_ratio = positive(name, value)?,
+            "predators.energy_gain" => self.predator_energy_gain = within(name, value, 1.)?,
+            "corpses.body_energy" => self.corpse_body_energy = non_negative(name, value)?,
+            "corpses.decay_rate" => self.corpse_decay_rate = positive(name, value)?,
+            "seeds.grid" => self.seeds_grid = switch(name, value)?,
+            "plants.initial" => self.initial_plants = whole(name, value, 0)?,
+            "plants.photosynthesis_rate" => self.plant_photosynthesis_rate = non_negative(name, value)?,
+            "plants.max_energy" => self.plant_max_energy = positive(name, value)?,
+            "plants.max_radius" => self.plant_max_radius = positive(name, value)?,
+            "plants.seed_interval" => self.plant_seed_interval = whole(name, value, 1)?,
+            "clock.day_length" => self.day_length = whole(name, value, 0)?,
+            "clock.days_per_season" => self.days_per_season = whole(name, value, 1)?,
+            "clock.night_light" => self.night_light = within(name, value, 1.)?,
+            "fields.cell_size" => self.field_cell_size = positive(name, value)?,
+            "cells.pheromone_deposit" => self.cell_pheromone_deposit = non_negative(name, value)?,
+            "plants.nutrient_uptake" => self.plant_nutrient_uptake = non_negative(name, value)?,
+            _ => return self.set_field_parameter(name, value),
+        }
+        Ok(())
+    }
+
+    /// Runs every parameter through the checks of `set_parameter`, and the
+    /// terrain through its own, for configs read from a file rather than set
+    /// one parameter at a time.
+    pub fn validate(&self) -> Result<(), String> {
+        let mut checked = self.clone();
+        for name in self.parameter_names() {
+            let value = self.parameter(&name).ok_or_else(|| format!("unknown parameter `{name}`"))?;
+            checked.set_parameter(&name, value)?;
+        }
+        self.world.validate()
+    }
+
+    /// `fields.<layer>.diffusion` and `fields.<layer>.decay`.
+    fn set_field_parameter(&mut self, name: &str, value: f64) -> Result<(), String> {
+        let unknown = || format!("unknown parameter `{name}`");
+        let (layer_name, setting) = name
+            .strip_prefix("fields.")
+            .and_then(|rest| rest.rsplit_once('.'))
+            .ok_or_else(unknown)?;
+        let layer = self
+            .field_layers
+            .iter_mut()
+            .find(|layer| layer.name == layer_name)
+            .ok_or_else(unknown)?;
+        match setting {
+            "diffusion" => layer.diffusion = within(name, value, 0.25)?,
+            "decay" => layer.decay = within(name, value, 1.)?,
+            _ => return Err(unknown()),
+        }
+        Ok(())
+    }
+}
+
+/// The names `SimulationConfig::set_parameter` takes, besides the field layers.
+const PARAMETERS: [&str; 33] = [
+    "board.width",
+    "board.height",
+    "seeds.step",
+    "seeds.energy",
+    "seeds.damping",
+    "seeds.grid",
+    "cells.initial",
+    "cells.radius",
+    "cells.initial_energy",
+    "cells.move_cost",
+    "cells.division_energy",
+    "cells.speed",
+    "cells.sensor_range",
+    "cells.mutation_rate",
+    "cells.pheromone_deposit",
+    "species.threshold",
+    "predators.initial",
+    "predators.attack_cost",
+    "predators.success_chance",
+    "predators.size_ratio",
+    "predators.energy_gain",
+    "corpses.body_energy",
+    "corpses.decay_rate",
+    "plants.initial",
+    "plants.photosynthesis_rate",
+    "plants.max_energy",
+    "plants.max_radius",
+    "plants.seed_interval",
+    "plants.nutrient_uptake",
+    "clock.day_length",
+    "clock.days_per_season",
+    "clock.night_light",
+    "fields.cell_size",
+];
+
+fn positive(name: &str, value: f64) -> Result<f32, String> {
+    if value > 0. {
+        Ok(value as f32)
+    } else {
+        Err(format!("`{name}` must be positive, got {value}"))
+    }
+}
+
+fn non_negative(name: &str, value: f64) -> Result<f32, String> {
+    if value >= 0. {
+        Ok(value as f32)
+    } else {
+        Err(format!("`{name}` must not be negative, got {value}"))
+    }
+}
+
+/// Between 0 and `max`, both included.
+fn within(name: &str, value: f64, max: f64) -> Result<f32, String> {
+    if (0. ..=max).contains(&value) {
+        Ok(value as f32)
+    } else {
+        Err(format!("`{name}` must be between 0 and {max}, got {value}"))
+    }
+}
+
+/// `0` for off, `1` for on.
+fn switch(name: &str, value: f64) -> Result<bool, String> {
+    if value == 0. || value == 1. {
+        Ok(value == 1.)
+    } else {
+        Err(format!("`{name}` must be 0 or 1, got {value}"))
+    }
+}
+
+fn whole(name: &str, value: f64, min: u32) -> Result<u32, String> {
+    if value.fract() == 0. && value >= min as f64 && value <= u32::MAX as f64 {
+        Ok(value as u32)
+    } else {
+        Err(format!("`{name}` must be a whole number of at least {min}, got {value}"))
+    }
+}
@@ -0,0 +1,220 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    core::{config::SimulationConfig, light::LightField, simulation::SimulationSet},
+    entities::world_board::WorldBoard,
+};
+
+/// Left behind by decomposing corpses and taken up by plants.
+pub const NUTRIENTS: &str = "nutrients";
+/// Trail laid by cells, followed by predators.
+pub const PHEROMONES: &str = "pheromones";
+/// Warmed by the light, relaxes towards the light field.
+pub const TEMPERATURE: &str = "temperature";
+
+pub struct FieldsPlugin;
+
+impl Plugin for FieldsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(PostUpdate, fields_step_system.in_set(SimulationSet));
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FieldLayerSettings {
+    pub name: String,
+    /// Share of the difference with its neighbours a grid cell evens out per tick, at most `0.25`.
+    pub diffusion: f32,
+    /// Share of its value a grid cell loses per tick.
+    pub decay: f32,
+}
+
+impl FieldLayerSettings {
+    pub fn new(name: &str, diffusion: f32, decay: f32) -> Self {
+        Self {
+            name: name.to_string(),
+            diffusion,
+            decay,
+        }
+    }
+}
+
+pub fn default_field_layers() -> Vec<FieldLayerSettings> {
+    vec![
+        FieldLayerSettings::new(NUTRIENTS, 0.02, 0.001),
+        FieldLayerSettings::new(PHEROMONES, 0.15, 0.01),
+        FieldLayerSettings::new(TEMPERATURE, 0.1, 0.01),
+    ]
+}
+
+#[derive(Clone)]
+struct FieldLayer {
+    name: String,
+    values: Vec<f32>,
+}
+
+/// Named scalar layers on a regular grid laid over the board.
+#[derive(Component, Clone)]
+pub struct ScalarFields {
+    origin: Vec2,
+    cell_size: f32,
+    width: usize,
+    height: usize,
+    layers: Vec<FieldLayer>,
+    scratch: Vec<f32>,
+}
+
+impl ScalarFields {
+    pub fn new(board_rect: Rect, cell_size: f32, layers: &[FieldLayerSettings]) -> Self {
+        let cell_size = cell_size.max(1.);
+        let width = (board_rect.width() / cell_size).ceil().max(1.) as usize;
+        let height = (board_rect.height() / cell_size).ceil().max(1.) as usize;
+        Self {
+            origin: board_rect.min,
+            cell_size,
+            width,
+            height,
+            layers: layers
+                .iter()
+                .map(|layer| FieldLayer {
+                    name: layer.name.clone(),
+                    values: vec![0.; width * height],
+                })
+                .collect(),
+            scratch: vec![0.; width * height],
+        }
+    }
+
+    pub fn size(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    pub fn layer_names(&self) -> impl Iterator<Item = &str> {
+        self.layers.iter().map(|layer| layer.name.as_str())
+    }
+
+    pub fn layer(&self, name: &str) -> Option<usize> {
+        self.layers.iter().position(|layer| layer.name == name)
+    }
+
+    /// Raw values of a layer, row by row from the bottom of the board.
+    pub fn values(&self, layer: usize) -> &[f32] {
+        &self.layers[layer].values
+    }
+
+    fn grid_position(&self, position: Vec2) -> Vec2 {
+        (position - self.origin) / self.cell_size - Vec2::splat(0.5)
+    }
+
+    fn index(&self, x: usize, y: usize) -> usize {
+        y * self.width + x
+    }
+
+    fn value_at_cell(&self, layer: usize, x: isize, y: isize) -> f32 {
+        let x = x.clamp(0, self.width as isize - 1) as usize;
+        let y = y.clamp(0, self.height as isize - 1) as usize;
+        self.layers[layer].values[self.index(x, y)]
+    }
+
+    /// Bilinear interpolation between the four nearest grid cells.
+    pub fn sample(&self, layer: usize, position: Vec2) -> f32 {
+        let grid = self.grid_position(position);
+        let (x0, y0) = (grid.x.floor() as isize, grid.y.floor() as isize);
+        let (tx, ty) = (grid.x - x0 as f32, grid.y - y0 as f32);
+        let bottom = self.value_at_cell(layer, x0, y0) * (1. - tx)
+            + self.value_at_cell(layer, x0 + 1, y0) * tx;
+        let top = self.value_at_cell(layer, x0, y0 + 1) * (1. - tx)
+            + self.value_at_cell(layer, x0 + 1, y0 + 1) * tx;
+        bottom * (1. - ty) + top * ty
+    }
+
+    /// Change of the layer per world unit at `position`, by central differences.
+    pub fn gradient(&self, layer: usize, position: Vec2) -> Vec2 {
+        let h = self.cell_size;
+        Vec2::new(
+            self.sample(layer, position + Vec2::X * h) - self.sample(layer, position - Vec2::X * h),
+            self.sample(layer, position + Vec2::Y * h) - self.sample(layer, position - Vec2::Y * h),
+        ) / (2. * h)
+    }
+
+    /// Adds `amount` to the grid cell under `position`; positions off the grid are ignored.
+    pub fn deposit(&mut self, layer: usize, position: Vec2, amount: f32) {
+        let Some(index) = self.cell_index(position) else { return };
+        self.layers[layer].values[index] += amount;
+    }
+
+    /// Removes up to `amount` from the grid cell under `position` and returns what was taken.
+    pub fn take(&mut self, layer: usize, position: Vec2, amount: f32) -> f32 {
+        let Some(index) = self.cell_index(position) else { return 0. };
+        let value = &mut self.layers[layer].values[index];
+        let taken = amount.min(*value).max(0.);
+        *value -= taken;
+        taken
+    }
+
+    fn cell_index(&self, position: Vec2) -> Option<usize> {
+        let grid = (position - self.origin) / self.cell_size;
+        if grid.x < 0. || grid.y < 0. {
+            return None;
+        }
+        let (x, y) = (grid.x as usize, grid.y as usize);
+        (x < self.width && y < self.height).then(|| self.index(x, y))
+    }
+
+    /// Centre of a grid cell in world coordinates.
+    pub fn cell_center(&self, x: usize, y: usize) -> Vec2 {
+        self.origin + (Vec2::new(x as f32, y as f32) + Vec2::splat(0.5)) * self.cell_size
+    }
+
+    /// One explicit diffusion step with closed borders, followed by decay.
+    fn step(&mut self, layer: usize, diffusion: f32, decay: f32) {
+        let diffusion = diffusion.clamp(0., 0.25);
+        let keep = (1. - decay).clamp(0., 1.);
+        let (width, height) = (self.width as isize, self.height as isize);
+        for y in 0..height {
+            for x in 0..width {
+                let center = self.value_at_cell(layer, x, y);
+                let neighbours = self.value_at_cell(layer, x - 1, y)
+                    + self.value_at_cell(layer, x + 1, y)
+                    + self.value_at_cell(layer, x, y - 1)
+                    + self.value_at_cell(layer, x, y + 1);
+                let index = self.index(x as usize, y as usize);
+                self.scratch[index] = (center + diffusion * (neighbours - 4. * center)) * keep;
+            }
+        }
+        std::mem::swap(&mut self.layers[layer].values, &mut self.scratch);
+    }
+}
+
+fn fields_step_system(
+    mut boards: Query<(&WorldBoard, &mut ScalarFields)>,
+    config: Res<SimulationConfig>,
+    light: Res<LightField>,
+) {
+    for (board, mut fields) in boards.iter_mut() {
+        if let Some(temperature) = fields.layer(TEMPERATURE) {
+            // Heating by `decay * light` makes the temperature settle at the light level.
+            let decay = config
+                .field_layers
+                .iter()
+                .find(|layer| layer.name == TEMPERATURE)
+                .map_or(0., |layer| layer.decay);
+            let (width, height) = fields.size();
+            for y in 0..height {
+                for x in 0..width {
+                    let center = fields.cell_center(x, y);
+                    let heat = decay * light.intensity_at(center, board.settings.rect);
+                    let index = fields.index(x, y);
+                    fields.layers[temperature].values[index] += heat;
+                }
+            }
+        }
+
+        for settings in &config.field_layers {
+            if let Some(layer) = fields.layer(&settings.name) {
+                fields.step(layer, settings.diffusion, settings.decay);
+            }
+        }
+    }
+}
@@ -0,0 +1,69 @@
+use bevy::prelude::*;
+
+use crate::{
+    core::{config::SimulationConfig, replay::*, simulation::*},
+    entities::{cells::*, seed::*},
+};
+
+/// Fixed physics step of every run, so a run only depends on its config and inputs.
+pub const HEADLESS_DT: f32 = 1. / 60.;
+
+/// Population numbers sampled after one tick of a headless run.
+#[derive(Clone, Copy, Debug)]
+pub struct TickSample {
+    pub tick: u64,
+    pub cells: usize,
+    pub predators: usize,
+    pub seeds: usize,
+}
+
+/// Builds an `App` that simulates the world without a window, renderer or UI.
+pub fn build_headless_app(config: SimulationConfig) -> App {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        TransformPlugin,
+        HierarchyPlugin,
+        AssetPlugin::default(),
+    ))
+        .init_asset::<Mesh>()
+        .init_asset::<ColorMaterial>()
+        .insert_resource(config)
+        .add_plugins(SimulationPlugin);
+
+    app.finish();
+    app.cleanup();
+    app
+}
+
+/// Runs `ticks` updates and returns the population after each of them.
+pub fn run_headless(config: SimulationConfig, ticks: u64) -> Vec<TickSample> {
+    sample_ticks(build_headless_app(config), ticks)
+}
+
+/// Plays a recorded run back to its end.
+pub fn run_headless_replay(replay: Replay) -> Vec<TickSample> {
+    let mut app = build_headless_app(replay.config.clone());
+    let ticks = replay.length;
+    app.insert_resource(ReplayState::playing(replay));
+    sample_ticks(app, ticks)
+}
+
+fn sample_ticks(mut app: App, ticks: u64) -> Vec<TickSample> {
+    let mut cells = app.world.query_filtered::<(), (With<Cell>, Without<Predator>)>();
+    let mut predators = app.world.query_filtered::<(), With<Predator>>();
+    let mut seeds = app.world.query_filtered::<(), With<Seed>>();
+
+    let mut samples = Vec::with_capacity(ticks as usize);
+    for _ in 0..ticks {
+        app.update();
+        samples.push(TickSample {
+            tick: app.world.resource::<SimulationTick>().0,
+            cells: cells.iter(&app.world).count(),
+            predators: predators.iter(&app.world).count(),
+            seeds: seeds.iter(&app.world).count(),
+        });
+    }
+    samples
+}
+
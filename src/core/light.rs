@@ -0,0 +1,30 @@
+use std::f32::consts::TAU;
+
+use bevy::prelude::*;
+
+/// Sunlight reaching the board, in `0..=1`. Bright and dark patches alternate
+/// across the board so producers do better in some places than in others.
+#[derive(Resource)]
+pub struct LightField {
+    /// Scales the whole field, e.g. for time of day.
+    pub brightness: f32,
+    /// Number of bright patches along each side of the board.
+    pub patches: f32,
+}
+
+impl Default for LightField {
+    fn default() -> Self {
+        Self {
+            brightness: 1.,
+            patches: 1.5,
+        }
+    }
+}
+
+impl LightField {
+    pub fn intensity_at(&self, position: Vec2, board_rect: Rect) -> f32 {
+        let uv = (position - board_rect.min) / board_rect.size();
+        let pattern = (uv.x * self.patches * TAU).sin() * (uv.y * self.patches * TAU).cos();
+        (self.brightness * (0.5 + 0.5 * pattern)).clamp(0., 1.)
+    }
+}
\ No newline at end of file
@@ -0,0 +1,210 @@
+use std::{collections::BTreeMap, fmt::Write as _};
+
+use bevy::prelude::*;
+
+use crate::{
+    core::simulation::{SimulationSet, SimulationTick},
+    entities::cells::*,
+};
+
+pub struct LineagePlugin;
+
+impl Plugin for LineagePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LineageRecords>()
+            .add_event::<CellDiedEvent>()
+            .add_systems(PostUpdate, record_deaths_system.in_set(SimulationSet));
+    }
+}
+
+/// Sent by whatever kills a cell; the cell is despawned by the cells plugin.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct CellDiedEvent {
+    pub entity: Entity,
+    pub id: CellId,
+    pub cause: DeathCause,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct LineageRecord {
+    pub parent: Option<CellId>,
+    pub birth_tick: u64,
+    pub death_tick: Option<u64>,
+    pub death_cause: Option<DeathCause>,
+}
+
+impl LineageRecord {
+    pub fn is_alive(&self) -> bool {
+        self.death_tick.is_none()
+    }
+}
+
+/// Every cell that ever lived in the run, with the parent it split from.
+#[derive(Resource, Clone, Default)]
+pub struct LineageRecords {
+    records: BTreeMap<CellId, LineageRecord>,
+    children: BTreeMap<CellId, Vec<CellId>>,
+    next_id: u64,
+}
+
+impl LineageRecords {
+    pub fn register_birth(&mut self, parent: Option<CellId>, tick: u64) -> CellId {
+        let id = CellId(self.next_id);
+        self.next_id += 1;
+        self.records.insert(id, LineageRecord {
+            parent,
+            birth_tick: tick,
+            death_tick: None,
+            death_cause: None,
+        });
+        if let Some(parent) = parent {
+            self.children.entry(parent).or_default().push(id);
+        }
+        id
+    }
+
+    pub fn register_death(&mut self, id: CellId, tick: u64, cause: DeathCause) {
+        if let Some(record) = self.records.get_mut(&id) {
+            if record.is_alive() {
+                record.death_tick = Some(tick);
+                record.death_cause = Some(cause);
+            }
+        }
+    }
+
+    /// Id the next cell born will get.
+    pub fn next_id(&self) -> u64 {
+        self.next_id
+    }
+
+    pub fn get(&self, id: CellId) -> Option<&LineageRecord> {
+        self.records.get(&id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    pub fn children(&self, id: CellId) -> &[CellId] {
+        self.children.get(&id).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn roots(&self) -> impl Iterator<Item = CellId> + '_ {
+        self.records
+            .iter()
+            .filter(|(_, record)| record.parent.is_none())
+            .map(|(id, _)| *id)
+    }
+
+    /// All cells descending from `ancestor`, not including the ancestor itself.
+    pub fn descendants(&self, ancestor: CellId) -> Vec<CellId> {
+        let mut descendants = Vec::new();
+        let mut stack = self.children(ancestor).to_vec();
+        while let Some(id) = stack.pop() {
+            descendants.push(id);
+            stack.extend_from_slice(self.children(id));
+        }
+        descendants
+    }
+
+    /// Newick tree of the run; branch lengths are lifetimes in ticks, cells still
+    /// alive are measured up to `current_tick`. Several founders become siblings
+    /// under an unnamed root.
+    pub fn to_newick(&self, current_tick: u64) -> String {
+        let roots: Vec<CellId> = self.roots().collect();
+        let mut out = String::new();
+        if roots.len() > 1 {
+            out.push('(');
+        }
+        for (i, root) in roots.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            self.write_newick_subtree(*root, current_tick, &mut out);
+        }
+        if roots.len() > 1 {
+            out.push(')');
+        }
+        out.push(';');
+        out
+    }
+
+    fn write_newick_subtree(&self, root: CellId, current_tick: u64, out: &mut String) {
+        enum Step {
+            Enter(CellId),
+            Separator,
+            Leave(CellId),
+        }
+
+        // Lineages can be thousands of generations deep, so walk them without recursion.
+        let mut stack = vec![Step::Enter(root)];
+        while let Some(step) = stack.pop() {
+            match step {
+                Step::Enter(id) => {
+                    let children = self.children(id);
+                    if children.is_empty() {
+                        self.write_newick_label(id, current_tick, out);
+                        continue;
+                    }
+                    out.push('(');
+                    stack.push(Step::Leave(id));
+                    for (i, child) in children.iter().enumerate().rev() {
+                        stack.push(Step::Enter(*child));
+                        if i > 0 {
+                            stack.push(Step::Separator);
+                        }
+                    }
+                }
+                Step::Separator => out.push(','),
+                Step::Leave(id) => {
+                    out.push(')');
+                    self.write_newick_label(id, current_tick, out);
+                }
+            }
+        }
+    }
+
+    fn write_newick_label(&self, id: CellId, current_tick: u64, out: &mut String) {
+        let record = &self.records[&id];
+        let end = record.death_tick.unwrap_or(current_tick);
+        let _ = write!(out, "c{}:{}", id.0, end.saturating_sub(record.birth_tick));
+    }
+
+    /// Graphviz graph with one node per cell and an edge from parent to child.
+    /// Living cells are filled.
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph lineage {\n    node [shape=box, fontsize=10];\n");
+        for (id, record) in &self.records {
+            let _ = write!(out, "    c{} [label=\"#{}\\nborn {}", id.0, id.0, record.birth_tick);
+            match (record.death_tick, record.death_cause) {
+                (Some(tick), Some(cause)) => {
+                    let _ = writeln!(out, "\\ndied {} ({})\"];", tick, cause.as_str());
+                }
+                _ => {
+                    out.push_str("\", style=filled, fillcolor=palegreen];\n");
+                }
+            }
+        }
+        for (parent, children) in &self.children {
+            for child in children {
+                let _ = writeln!(out, "    c{} -> c{};", parent.0, child.0);
+            }
+        }
+        out.push_str("}\n");
+        out
+    }
+}
+
+fn record_deaths_system(
+    mut events: EventReader<CellDiedEvent>,
+    mut lineage: ResMut<LineageRecords>,
+    tick: Res<SimulationTick>,
+) {
+    for ev in events.read() {
+        lineage.register_death(ev.id, tick.0, ev.cause);
+    }
+}
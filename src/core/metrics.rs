@@ -0,0 +1,134 @@
+use std::collections::BTreeMap;
+
+use bevy::prelude::*;
+
+use crate::{
+    core::{headless::HEADLESS_DT, simulation::{SimulationSet, SimulationTick}, species::*},
+    entities::{cells::*, plant::Plant, seed::*, world_board::*},
+    utils::time_series::TimeSeries,
+};
+
+/// Buckets kept per resolution of a series: ten seconds of single ticks.
+const METRICS_RESOLUTION: usize = 600;
+
+pub struct MetricsPlugin;
+
+impl Plugin for MetricsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<WorldMetrics>()
+            .add_systems(
+                PostUpdate,
+                update_metrics_system
+                    .after(species_population_system)
+                    .in_set(SimulationSet),
+            );
+    }
+}
+
+/// Counts of the run so far, one sample per tick.
+#[derive(Resource, Clone)]
+pub struct WorldMetrics {
+    pub seeds_count: TimeSeries,
+    pub cells_count: TimeSeries,
+    pub predators_count: TimeSeries,
+    pub plants_count: TimeSeries,
+    /// Population per species, from the tick it was first counted until it died out.
+    pub species_count: BTreeMap<SpeciesId, TimeSeries>,
+    /// One entry per board, in spawn order.
+    pub boards: Vec<BoardMetrics>,
+}
+
+#[derive(Clone)]
+pub struct BoardMetrics {
+    pub name: String,
+    pub seeds_count: TimeSeries,
+    pub cells_count: TimeSeries,
+    pub predators_count: TimeSeries,
+}
+
+impl Default for WorldMetrics {
+    fn default() -> Self {
+        Self {
+            seeds_count: metrics_buffer(),
+            cells_count: metrics_buffer(),
+            predators_count: metrics_buffer(),
+            plants_count: metrics_buffer(),
+            species_count: BTreeMap::new(),
+            boards: Vec::new(),
+        }
+    }
+}
+
+fn metrics_buffer() -> TimeSeries {
+    TimeSeries::new(METRICS_RESOLUTION)
+}
+
+/// Simulated seconds at the start of `tick`, one physics step per tick.
+pub fn sim_seconds(tick: u64) -> f64 {
+    tick as f64 * HEADLESS_DT as f64
+}
+
+/// Ticks that take `seconds` of simulated time.
+pub fn ticks_in(seconds: f64) -> u64 {
+    (seconds / HEADLESS_DT as f64).round() as u64
+}
+
+#[allow(clippy::too_many_arguments)]
+fn update_metrics_system(
+    cells: Query<&Transform, (With<Cell>, Without<Predator>)>,
+    predators: Query<&Transform, With<Predator>>,
+    plants: Query<(), With<Plant>>,
+    seeds: Query<&Transform, With<Seed>>,
+    boards: Query<&WorldBoard>,
+    species: Res<SpeciesRegistry>,
+    tick: Res<SimulationTick>,
+    mut metrics: ResMut<WorldMetrics>,
+) {
+    let tick = tick.0;
+    metrics.cells_count.push(tick, cells.iter().count() as f64);
+    metrics.seeds_count.push(tick, seeds.iter().count() as f64);
+    metrics.predators_count.push(tick, predators.iter().count() as f64);
+    metrics.plants_count.push(tick, plants.iter().count() as f64);
+
+    if metrics.boards.len() != boards.iter().count() {
+        metrics.boards = boards
+            .iter()
+            .map(|board| BoardMetrics {
+                name: board.settings.name.clone(),
+                seeds_count: metrics_buffer(),
+                cells_count: metrics_buffer(),
+                predators_count: metrics_buffer(),
+            })
+            .collect();
+    }
+    let per_board = |transforms: &mut dyn Iterator<Item = &Transform>| {
+        let mut counts = vec![0usize; metrics.boards.len()];
+        for transform in transforms {
+            if let Some(index) = board_index_at(boards.iter(), transform.translation.truncate()) {
+                counts[index] += 1;
+            }
+        }
+        counts
+    };
+    let cells_per_board = per_board(&mut cells.iter());
+    let seeds_per_board = per_board(&mut seeds.iter());
+    let predators_per_board = per_board(&mut predators.iter());
+    for (index, board) in metrics.boards.iter_mut().enumerate() {
+        board.cells_count.push(tick, cells_per_board[index] as f64);
+        board.seeds_count.push(tick, seeds_per_board[index] as f64);
+        board.predators_count.push(tick, predators_per_board[index] as f64);
+    }
+
+    for species in species.iter() {
+        let counted = metrics.species_count.get(&species.id).and_then(TimeSeries::last);
+        // A species is sampled while alive, and once more with the zero it died out at.
+        if species.population == 0 && counted.map_or(true, |count| count == 0.) {
+            continue;
+        }
+        metrics
+            .species_count
+            .entry(species.id)
+            .or_insert_with(metrics_buffer)
+            .push(tick, species.population as f64);
+    }
+}
@@ -1 +1,13 @@
-pub(crate) mod network;
\ No newline at end of file
+pub mod clock;
+pub mod config;
+pub mod fields;
+pub mod headless;
+pub mod light;
+pub mod lineage;
+pub mod metrics;
+pub mod network;
+pub mod replay;
+pub mod shared_handles;
+pub mod simulation;
+pub mod species;
+pub mod sweep;
\ No newline at end of file
@@ -1,10 +1,42 @@
-use bevy::math::Vec2Swizzles;
+use std::collections::BTreeMap;
+
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 
 use crate::entities::cells::*;
 
 pub trait CapNetwork {
     fn activate(&mut self, position: Vec3, center_of_mass: Vec2) -> Vec3;
+
+    /// Networks with the same topology can be evaluated together by `BatchedNetworks`.
+    fn topology(&self) -> NetworkTopology;
+
+    /// `activate` for [`LANES`] networks of this one's topology at once, bit for bit.
+    fn lane_kernel(&self) -> LaneKernel;
+}
+
+/// Lanes evaluated together; a multiple of the widest SIMD register in `f32`s.
+pub const LANES: usize = 8;
+
+/// Evaluates a lane of networks from their positions and targets, one slice of
+/// [`LANES`] values per coordinate: `(px, py, tx, ty, ox, oy)`.
+pub type LaneKernel = fn(&[f32], &[f32], &[f32], &[f32], &mut [f32], &mut [f32]);
+
+/// Layout of a network, the key brains are grouped by for batched evaluation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum NetworkTopology {
+    /// Heads straight for the target.
+    Seeker,
+}
+
+/// How `cells_think_system` runs the brains.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BrainBackend {
+    /// One `CapNetwork::activate` call per cell.
+    #[default]
+    Scalar,
+    /// Brains grouped by topology and evaluated as batches by `BatchedNetworks`.
+    Batched,
 }
 
 impl CapNetwork for Cell {
@@ -22,4 +54,127 @@ impl CapNetwork for Cell {
 
         vector_to_point.normalize_or_zero().extend(0.)
     }
-}
\ No newline at end of file
+
+    fn topology(&self) -> NetworkTopology {
+        NetworkTopology::Seeker
+    }
+
+    fn lane_kernel(&self) -> LaneKernel {
+        seeker_lanes
+    }
+}
+
+/// Inputs and outputs of every brain of one topology, one array per value.
+struct Batch {
+    kernel: LaneKernel,
+    entities: Vec<Entity>,
+    position_x: Vec<f32>,
+    position_y: Vec<f32>,
+    target_x: Vec<f32>,
+    target_y: Vec<f32>,
+    output_x: Vec<f32>,
+    output_y: Vec<f32>,
+}
+
+impl Batch {
+    fn new(kernel: LaneKernel) -> Self {
+        Self {
+            kernel,
+            entities: Vec::new(),
+            position_x: Vec::new(),
+            position_y: Vec::new(),
+            target_x: Vec::new(),
+            target_y: Vec::new(),
+            output_x: Vec::new(),
+            output_y: Vec::new(),
+        }
+    }
+
+    fn clear(&mut self) {
+        self.entities.clear();
+        self.position_x.clear();
+        self.position_y.clear();
+        self.target_x.clear();
+        self.target_y.clear();
+        self.output_x.clear();
+        self.output_y.clear();
+    }
+
+    /// Pads the inputs to whole lanes, the padding is dropped by `results`.
+    fn evaluate(&mut self) {
+        let len = (self.entities.len() + LANES - 1) / LANES * LANES;
+        for values in [&mut self.position_x, &mut self.position_y, &mut self.target_x, &mut self.target_y] {
+            values.resize(len, 0.);
+        }
+        self.output_x.resize(len, 0.);
+        self.output_y.resize(len, 0.);
+
+        let inputs = self
+            .position_x
+            .chunks_exact(LANES)
+            .zip(self.position_y.chunks_exact(LANES))
+            .zip(self.target_x.chunks_exact(LANES))
+            .zip(self.target_y.chunks_exact(LANES));
+        let outputs = self.output_x.chunks_exact_mut(LANES).zip(self.output_y.chunks_exact_mut(LANES));
+        for ((((px, py), tx), ty), (ox, oy)) in inputs.zip(outputs) {
+            (self.kernel)(px, py, tx, ty, ox, oy);
+        }
+    }
+}
+
+/// `Vec2::normalize_or_zero` of `target - position` for a lane of brains, with
+/// the same operations in the same order, so the results match bit for bit.
+fn seeker_lanes(px: &[f32], py: &[f32], tx: &[f32], ty: &[f32], ox: &mut [f32], oy: &mut [f32]) {
+    let mut dx = [0.; LANES];
+    let mut dy = [0.; LANES];
+    let mut recip = [0.; LANES];
+    for i in 0..LANES {
+        dx[i] = tx[i] - px[i];
+        dy[i] = ty[i] - py[i];
+    }
+    for i in 0..LANES {
+        recip[i] = 1. / (dx[i] * dx[i] + dy[i] * dy[i]).sqrt();
+    }
+    for i in 0..LANES {
+        let valid = recip[i].is_finite() && recip[i] > 0.;
+        ox[i] = if valid { dx[i] * recip[i] } else { 0. };
+        oy[i] = if valid { dy[i] * recip[i] } else { 0. };
+    }
+}
+
+/// Structure-of-arrays store for the `Batched` brain backend.
+#[derive(Default)]
+pub struct BatchedNetworks {
+    batches: BTreeMap<NetworkTopology, Batch>,
+}
+
+impl BatchedNetworks {
+    /// Empties every batch and keeps the allocations for the next tick.
+    pub fn clear(&mut self) {
+        self.batches.values_mut().for_each(Batch::clear);
+    }
+
+    pub fn push(&mut self, entity: Entity, network: &impl CapNetwork, position: Vec3, center_of_mass: Vec2) {
+        let batch = self.batches.entry(network.topology()).or_insert_with(|| Batch::new(network.lane_kernel()));
+        batch.entities.push(entity);
+        batch.position_x.push(position.x);
+        batch.position_y.push(position.y);
+        batch.target_x.push(center_of_mass.x);
+        batch.target_y.push(center_of_mass.y);
+    }
+
+    pub fn evaluate(&mut self) {
+        self.batches.values_mut().for_each(Batch::evaluate);
+    }
+
+    /// Output of every pushed brain, as `CapNetwork::activate` would return it.
+    pub fn results(&self) -> impl Iterator<Item = (Entity, Vec3)> + '_ {
+        self.batches.values().flat_map(|batch| {
+            batch
+                .entities
+                .iter()
+                .enumerate()
+                .map(|(i, &entity)| (entity, Vec3::new(batch.output_x[i], batch.output_y[i], 0.)))
+        })
+    }
+}
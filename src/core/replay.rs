@@ -0,0 +1,305 @@
+use std::{error::Error, fs, path::Path, time::Duration};
+
+use bevy::{
+    app::MainScheduleOrder,
+    ecs::{event::ManualEventReader, schedule::ScheduleLabel},
+    prelude::*,
+    utils::Instant,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    actions::{cells_plugin::SpawnCellEvent, terrain_plugin::terrain_sync_action},
+    core::{
+        config::SimulationConfig,
+        lineage::CellDiedEvent,
+        simulation::{restart_world, SimulationSet, SimulationTick},
+        species::SpeciesId,
+    },
+    entities::{cells::*, terrain::*},
+};
+
+/// Wall time a frame may spend fast-forwarding to a seek target.
+const SEEK_FRAME_BUDGET: Duration = Duration::from_millis(50);
+
+/// Records what the user does to the world and plays recordings back.
+pub struct ReplayPlugin;
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        // Updated by `apply_user_input_system` rather than every frame, so inputs
+        // made while the world is paused wait for it instead of expiring.
+        app.init_resource::<Events<UserInput>>()
+            .init_resource::<ReplayState>()
+            .init_schedule(ReplaySeek)
+            .add_systems(Startup, replay_start_system)
+            .add_systems(First, apply_user_input_system.before(terrain_sync_action).in_set(SimulationSet))
+            .add_systems(ReplaySeek, (replay_restart_system, replay_seek_system).chain());
+        app.world.resource_mut::<MainScheduleOrder>().insert_after(Last, ReplaySeek);
+    }
+}
+
+/// Runs after `Last`, where whole extra ticks can be run while seeking.
+#[derive(ScheduleLabel, Clone, Debug, PartialEq, Eq, Hash)]
+struct ReplaySeek;
+
+/// Something the user did to the world. The UI sends these instead of changing
+/// the world directly, so every one of them lands in the recording.
+#[derive(Event, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum UserInput {
+    SpawnCell { position: [f32; 2], predator: bool },
+    AddObstacle(ObstacleShape),
+    AddTerrain(TerrainRegion),
+    /// Removes what `WorldLayout::erase_at` finds there.
+    Erase { position: [f32; 2] },
+    SetWorld(WorldLayout),
+    /// A [`SimulationConfig::set_parameter`] call.
+    SetParameter { name: String, value: f64 },
+    /// Kills every living cell of the species.
+    KillSpecies(u32),
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RecordedInput {
+    /// `SimulationTick` of the update the input was applied in.
+    pub tick: u64,
+    pub input: UserInput,
+}
+
+/// A run as the config it started from and what the user did during it.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Replay {
+    pub config: SimulationConfig,
+    pub inputs: Vec<RecordedInput>,
+    /// Ticks the recording covers.
+    pub length: u64,
+}
+
+impl Replay {
+    /// Reads a replay, turning down a `config` that `set_parameter` would not
+    /// accept and terrain that cells could not move through.
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let text = fs::read_to_string(path)?;
+        let replay: Self = ron::from_str(&text)?;
+        replay.config.validate()?;
+        for recorded in &replay.inputs {
+            match &recorded.input {
+                UserInput::AddTerrain(region) => region.validate()?,
+                UserInput::SetWorld(layout) => layout.validate()?,
+                _ => {}
+            }
+        }
+        Ok(replay)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        let text = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())?;
+        fs::write(path, text)?;
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ReplayMode {
+    /// User inputs are applied and appended to the replay.
+    #[default]
+    Recording,
+    /// Recorded inputs are applied and user inputs dropped until the replay ends,
+    /// then recording goes on from there.
+    Playing,
+}
+
+#[derive(Resource, Default)]
+pub struct ReplayState {
+    pub replay: Replay,
+    pub mode: ReplayMode,
+    /// Tick the world is being fast-forwarded to.
+    pub seek: Option<u64>,
+    /// Whether the world starts over from the current config at the end of the frame.
+    restart: bool,
+    /// Index of the next recorded input to play.
+    cursor: usize,
+}
+
+impl ReplayState {
+    pub fn playing(replay: Replay) -> Self {
+        Self {
+            replay,
+            mode: ReplayMode::Playing,
+            seek: None,
+            restart: false,
+            cursor: 0,
+        }
+    }
+
+    /// Starts a new run from the current config, parameters only read at the
+    /// start included, dropping the recording so far.
+    pub fn restart(&mut self) {
+        self.restart = true;
+    }
+
+    /// Ticks that can be seeked to.
+    pub fn length(&self, tick: u64) -> u64 {
+        match self.mode {
+            ReplayMode::Recording => tick,
+            ReplayMode::Playing => self.replay.length.max(tick),
+        }
+    }
+
+    /// The recording so far, ready to be saved.
+    pub fn recording(&self, tick: u64) -> Replay {
+        Replay {
+            length: self.length(tick),
+            ..self.replay.clone()
+        }
+    }
+
+    /// Jumps to `target`, replaying the recording from its start if it lies behind `tick`.
+    pub fn seek(&mut self, target: u64, tick: u64) {
+        if self.mode == ReplayMode::Recording {
+            self.replay.length = tick;
+        }
+        let target = target.min(self.length(tick));
+        if target == tick {
+            self.seek = None;
+            return;
+        }
+        self.mode = ReplayMode::Playing;
+        self.seek = Some(target);
+    }
+
+    /// Stops playing back and records from `tick` on; later recorded inputs are dropped.
+    pub fn take_over(&mut self, tick: u64) {
+        self.mode = ReplayMode::Recording;
+        self.seek = None;
+        self.replay.inputs.retain(|recorded| recorded.tick < tick);
+        self.cursor = self.replay.inputs.len();
+    }
+
+    /// Plays the recording from its start, after the world was restarted.
+    fn play_from_start(&mut self) {
+        self.mode = ReplayMode::Playing;
+        self.cursor = 0;
+    }
+}
+
+/// A recording starts from the config the world was set up with.
+fn replay_start_system(mut state: ResMut<ReplayState>, config: Res<SimulationConfig>) {
+    if state.mode == ReplayMode::Recording {
+        state.replay.config = config.clone();
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn apply_user_input_system(
+    mut live: ResMut<Events<UserInput>>,
+    mut reader: Local<ManualEventReader<UserInput>>,
+    mut state: ResMut<ReplayState>,
+    tick: Res<SimulationTick>,
+    mut config: ResMut<SimulationConfig>,
+    mut spawn_events: EventWriter<SpawnCellEvent>,
+    mut died_events: EventWriter<CellDiedEvent>,
+    cells: Query<(Entity, &CellId, &SpeciesId)>,
+) {
+    let state = &mut *state;
+    let inputs: Vec<UserInput> = match state.mode {
+        ReplayMode::Recording => {
+            let inputs: Vec<UserInput> = reader.read(&live).cloned().collect();
+            state.replay.inputs.extend(inputs.iter().map(|input| RecordedInput {
+                tick: tick.0,
+                input: input.clone(),
+            }));
+            state.cursor = state.replay.inputs.len();
+            inputs
+        }
+        ReplayMode::Playing => {
+            reader.clear(&live);
+            let start = state.cursor;
+            while state
+                .replay
+                .inputs
+                .get(state.cursor)
+                .is_some_and(|recorded| recorded.tick <= tick.0)
+            {
+                state.cursor += 1;
+            }
+            if state.cursor == state.replay.inputs.len() && tick.0 >= state.replay.length {
+                state.mode = ReplayMode::Recording;
+            }
+            state.replay.inputs[start..state.cursor]
+                .iter()
+                .map(|recorded| recorded.input.clone())
+                .collect()
+        }
+    };
+
+    for input in inputs {
+        match input {
+            UserInput::SpawnCell { position, predator } => {
+                let position = Vec2::from(position);
+                spawn_events.send(if predator {
+                    SpawnCellEvent::predator(position)
+                } else {
+                    SpawnCellEvent::new(position)
+                });
+            }
+            UserInput::AddObstacle(shape) => config.world.obstacles.push(shape),
+            UserInput::AddTerrain(region) => config.world.terrain.push(region),
+            UserInput::Erase { position } => config.world.erase_at(Vec2::from(position)),
+            UserInput::SetWorld(layout) => config.world = layout,
+            UserInput::SetParameter { name, value } => {
+                if let Err(err) = config.set_parameter(&name, value) {
+                    warn!("{err}");
+                }
+            }
+            UserInput::KillSpecies(species) => {
+                for (entity, &id, _) in cells.iter().filter(|(_, _, of)| of.0 == species) {
+                    died_events.send(CellDiedEvent {
+                        entity,
+                        id,
+                        cause: DeathCause::Killed,
+                    });
+                }
+            }
+        }
+    }
+    live.update();
+}
+
+/// Sets the world up again from the current config, recording a new run from it.
+fn replay_restart_system(world: &mut World) {
+    if !world.resource::<ReplayState>().restart {
+        return;
+    }
+    let config = world.resource::<SimulationConfig>().clone();
+    restart_world(world, config.clone());
+    world.insert_resource(ReplayState {
+        replay: Replay { config, ..default() },
+        ..default()
+    });
+}
+
+/// Moves the world towards `ReplayState::seek`, restarting it first when the
+/// target is in the past, and running as many extra ticks as fit in a frame.
+/// A target behind is always replayed from the start: the physics world keeps
+/// contact impulses between steps that no copy of the ECS state brings back.
+fn replay_seek_system(world: &mut World) {
+    let Some(target) = world.resource::<ReplayState>().seek else { return };
+    if target < world.resource::<SimulationTick>().0 {
+        let config = world.resource::<ReplayState>().replay.config.clone();
+        restart_world(world, config);
+        // The tick before may have run off the end of the replay and gone back to recording.
+        world.resource_mut::<ReplayState>().play_from_start();
+    }
+
+    let started = Instant::now();
+    while world.resource::<SimulationTick>().0 < target && started.elapsed() < SEEK_FRAME_BUDGET {
+        for label in [First.intern(), PreUpdate.intern(), Update.intern(), PostUpdate.intern(), Last.intern()] {
+            // A schedule nothing was added to does not exist, and has nothing to run.
+            let _ = world.try_run_schedule(label);
+        }
+    }
+    if world.resource::<SimulationTick>().0 >= target {
+        world.resource_mut::<ReplayState>().seek = None;
+    }
+}
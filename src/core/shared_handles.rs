@@ -0,0 +1,60 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+
+use crate::core::species::SpeciesId;
+
+/// Side of the square drawn for a seed.
+pub const SEED_SIZE: f32 = 3.;
+
+/// Meshes and materials shared by every entity of a kind, so the asset count
+/// stays flat as the population grows and sprites of a kind batch together.
+/// Round shapes are unit sized and scaled by the entity's transform.
+#[derive(Resource)]
+pub struct SharedHandles {
+    pub seed_mesh: Handle<Mesh>,
+    pub seed_material: Handle<ColorMaterial>,
+    pub unit_circle: Handle<Mesh>,
+    pub unit_triangle: Handle<Mesh>,
+    pub unit_hexagon: Handle<Mesh>,
+    pub plant_material: Handle<ColorMaterial>,
+    pub corpse_material: Handle<ColorMaterial>,
+    species_materials: HashMap<SpeciesId, Handle<ColorMaterial>>,
+}
+
+impl FromWorld for SharedHandles {
+    fn from_world(world: &mut World) -> Self {
+        let mut meshes = world.resource_mut::<Assets<Mesh>>();
+        let seed_mesh = meshes.add(Rectangle::from_size(Vec2::splat(SEED_SIZE)));
+        let unit_circle = meshes.add(Circle { radius: 1. });
+        let unit_triangle = meshes.add(RegularPolygon::new(1., 3));
+        let unit_hexagon = meshes.add(RegularPolygon::new(1., 6));
+
+        let mut materials = world.resource_mut::<Assets<ColorMaterial>>();
+        Self {
+            seed_mesh,
+            seed_material: materials.add(ColorMaterial::from(Color::YELLOW)),
+            unit_circle,
+            unit_triangle,
+            unit_hexagon,
+            plant_material: materials.add(ColorMaterial::from(Color::rgb(0.2, 0.7, 0.3))),
+            corpse_material: materials.add(ColorMaterial::from(Color::rgb(0.35, 0.3, 0.3))),
+            species_materials: HashMap::new(),
+        }
+    }
+}
+
+impl SharedHandles {
+    /// Material of a species, made on first use.
+    pub fn species_material(
+        &mut self,
+        materials: &mut Assets<ColorMaterial>,
+        species: SpeciesId,
+        color: Color,
+    ) -> Handle<ColorMaterial> {
+        self.species_materials
+            .entry(species)
+            .or_insert_with(|| materials.add(ColorMaterial::from(color)))
+            .clone()
+    }
+}
@@ -0,0 +1,183 @@
+use bevy::{
+    ecs::{event::EventUpdates, query::QueryFilter, schedule::ScheduleLabel, system::RunSystemOnce},
+    prelude::*,
+};
+use bevy_rapier2d::{
+    pipeline::{CollisionEvent, ContactForceEvent},
+    plugin::{NoUserData, PhysicsSet, RapierConfiguration, RapierContext, RapierPhysicsPlugin, TimestepMode},
+};
+use rand::{rngs::StdRng, SeedableRng};
+
+use crate::{
+    actions::{board_plugin::*, cells_plugin::*, corpses_plugin::*, migration_plugin::*, plants_plugin::*, seeds_plugin::*, terrain_plugin::*},
+    core::{clock::*, config::*, fields::*, headless::HEADLESS_DT, light::*, lineage::*, metrics::*, replay::*, shared_handles::*, species::*},
+    entities::{cells::Cell, corpse::Corpse, plant::Plant, seed::Seed, terrain::*, world_board::WorldBoard},
+};
+
+/// Everything needed to run the world, without windows, cameras or UI.
+pub struct SimulationPlugin;
+
+impl Plugin for SimulationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SimulationConfig>()
+            .init_resource::<SimulationRng>()
+            .init_resource::<SimulationTick>()
+            .init_resource::<SimulationPaused>()
+            .init_resource::<TickRunning>()
+            .init_resource::<HeldCollisions>()
+            .init_resource::<LightField>()
+            .init_resource::<SharedHandles>()
+            // A fixed step, in the window too, so a recorded run replays identically.
+            .insert_resource(RapierConfiguration {
+                gravity: Vec2::new(0., 0.),
+                timestep_mode: TimestepMode::Fixed {
+                    dt: HEADLESS_DT,
+                    substeps: 1,
+                },
+                ..default()
+            })
+            .add_plugins(RapierPhysicsPlugin::<NoUserData>::default())
+            .add_plugins((
+                ClockPlugin,
+                FieldsPlugin,
+                WorldBoardPlugin,
+                TerrainPlugin,
+                SeedsPlugin,
+                PlantsPlugin,
+                CellsPlugin,
+                MigrationPlugin,
+                CorpsesPlugin,
+                LineagePlugin,
+                SpeciesPlugin,
+                MetricsPlugin,
+                ReplayPlugin,
+            ))
+            .add_systems(First, start_frame_system.before(EventUpdates).before(SimulationSet))
+            .add_systems(Last, advance_tick_system.in_set(SimulationSet));
+        for schedule in [First.intern(), Update.intern(), PostUpdate.intern(), Last.intern()] {
+            app.configure_sets(schedule, SimulationSet.run_if(simulation_running));
+        }
+        app.configure_sets(
+            PostUpdate,
+            (PhysicsSet::SyncBackend, PhysicsSet::StepSimulation, PhysicsSet::Writeback).in_set(SimulationSet),
+        );
+    }
+}
+
+/// Systems that advance the world, held back while it is paused.
+#[derive(SystemSet, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SimulationSet;
+
+/// Whether the world stands still. The UI keeps running, and user inputs wait
+/// for the world to go on.
+#[derive(Resource, Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SimulationPaused(pub bool);
+
+/// Whether this frame runs a tick, settled when it starts so that pausing or
+/// seeking halfway through a frame never cuts a tick in two.
+#[derive(Resource, Default)]
+struct TickRunning(bool);
+
+/// Collisions of the last physics step before a pause, which the next tick
+/// reads once the world goes on.
+#[derive(Resource, Default)]
+struct HeldCollisions(Vec<CollisionEvent>);
+
+fn simulation_running(running: Res<TickRunning>) -> bool {
+    running.0
+}
+
+/// Seeking runs the world even while paused, to get it to the target. Events
+/// only live for two frames, so while paused the collisions are set aside.
+fn start_frame_system(
+    paused: Res<SimulationPaused>,
+    replay: Res<ReplayState>,
+    mut running: ResMut<TickRunning>,
+    mut held: ResMut<HeldCollisions>,
+    mut collisions: ResMut<Events<CollisionEvent>>,
+) {
+    running.0 = !paused.0 || replay.seek.is_some();
+    if running.0 {
+        collisions.send_batch(held.0.drain(..));
+    } else {
+        held.0.extend(collisions.iter_current_update_events().cloned());
+        collisions.clear();
+    }
+}
+
+/// Number of finished `Update` passes since the run started.
+#[derive(Resource, Default, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SimulationTick(pub u64);
+
+/// Single random source of the world, seeded from [`SimulationConfig::rng_seed`].
+#[derive(Resource)]
+pub struct SimulationRng(pub StdRng);
+
+impl FromWorld for SimulationRng {
+    fn from_world(world: &mut World) -> Self {
+        let seed = world.resource::<SimulationConfig>().rng_seed;
+        Self(StdRng::seed_from_u64(seed))
+    }
+}
+
+pub(crate) fn advance_tick_system(mut tick: ResMut<SimulationTick>) {
+    tick.0 += 1;
+}
+
+/// Clears the world and sets it up again from `config`, the same way the app
+/// starts, so the run that follows matches a fresh one with that config.
+pub fn restart_world(world: &mut World, config: SimulationConfig) {
+    despawn_world::<Or<(With<WorldBoard>, WorldPopulation)>>(world);
+
+    world.insert_resource(config);
+    // Every collider is gone, a fresh context allocates handles like a new app does.
+    world.insert_resource(RapierContext::default());
+    world.insert_resource(SimulationTick::default());
+    let rng = SimulationRng::from_world(world);
+    world.insert_resource(rng);
+    world.insert_resource(WorldClock::default());
+    world.insert_resource(LightField::default());
+    world.insert_resource(LineageRecords::default());
+    world.insert_resource(SpeciesRegistry::default());
+    world.insert_resource(WorldMetrics::default());
+    world.insert_resource(SpawnedWorld::default());
+    let handles = SharedHandles::from_world(world);
+    world.insert_resource(handles);
+    clear_world_events(world);
+
+    // The startup systems of the plugins above, in their startup order.
+    world.run_system_once(board_spawn_action);
+    world.run_system_once(seeds_spawn_action);
+    world.run_system_once(cells_initial_spawn_action);
+    world.run_system_once(plants_spawn_action);
+}
+
+/// What lives on the boards.
+pub(crate) type Inhabitants = Or<(With<Seed>, With<Cell>, With<Plant>, With<Corpse>)>;
+
+/// Obstacles and terrain, respawned from the config.
+pub(crate) type LaidOut = Or<(With<Obstacle>, With<TerrainPatch>)>;
+
+/// Everything on the boards: what lives there, and what is laid out on them.
+pub(crate) type WorldPopulation = Or<(Inhabitants, LaidOut)>;
+
+pub(crate) fn despawn_world<F: QueryFilter>(world: &mut World) {
+    let entities: Vec<Entity> = world.query_filtered::<Entity, F>().iter(world).collect();
+    for entity in entities {
+        if let Some(entity) = world.get_entity_mut(entity) {
+            entity.despawn_recursive();
+        }
+    }
+}
+
+/// Drops events about entities of a world that was just cleared.
+pub(crate) fn clear_world_events(world: &mut World) {
+    world.resource_mut::<Events<SpawnCellEvent>>().clear();
+    world.resource_mut::<Events<CellAteEvent>>().clear();
+    world.resource_mut::<Events<SpawnSeedEvent>>().clear();
+    world.resource_mut::<Events<SpawnCorpseEvent>>().clear();
+    world.resource_mut::<Events<CellDiedEvent>>().clear();
+    world.resource_mut::<Events<CollisionEvent>>().clear();
+    world.resource_mut::<HeldCollisions>().0.clear();
+    world.resource_mut::<Events<ContactForceEvent>>().clear();
+}
@@ -0,0 +1,89 @@
+use bevy::prelude::*;
+
+use crate::{
+    core::simulation::SimulationSet,
+    entities::{cells::*, genome::Genome},
+};
+
+pub struct SpeciesPlugin;
+
+impl Plugin for SpeciesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SpeciesRegistry>()
+            .add_systems(PostUpdate, species_population_system.in_set(SimulationSet));
+    }
+}
+
+/// Stable id of a species; never reused within a run.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SpeciesId(pub u32);
+
+#[derive(Clone, Debug)]
+pub struct Species {
+    pub id: SpeciesId,
+    /// Genome of the first member; later cells join when they are close enough to it.
+    pub representative: Genome,
+    pub predator: bool,
+    pub color: Color,
+    pub population: usize,
+}
+
+#[derive(Resource, Clone, Default)]
+pub struct SpeciesRegistry {
+    species: Vec<Species>,
+}
+
+impl SpeciesRegistry {
+    /// Returns the first species of the same kind whose representative is within
+    /// `threshold` genetic distance, or founds a new one.
+    pub fn classify(&mut self, genome: &Genome, predator: bool, threshold: f32) -> SpeciesId {
+        if let Some(species) = self.species.iter().find(|species| {
+            species.predator == predator && species.representative.distance(genome) <= threshold
+        }) {
+            return species.id;
+        }
+
+        let id = SpeciesId(self.species.len() as u32);
+        self.species.push(Species {
+            id,
+            representative: *genome,
+            predator,
+            color: species_color(id),
+            population: 0,
+        });
+        id
+    }
+
+    pub fn get(&self, id: SpeciesId) -> Option<&Species> {
+        self.species.get(id.0 as usize)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Species> {
+        self.species.iter()
+    }
+
+    pub fn living(&self) -> impl Iterator<Item = &Species> {
+        self.species.iter().filter(|species| species.population > 0)
+    }
+}
+
+/// Spreads hues by the golden angle so neighbouring ids get distinct colours.
+/// The first species keeps the purple cells always had.
+pub fn species_color(id: SpeciesId) -> Color {
+    let hue = (280. + id.0 as f32 * 137.508) % 360.;
+    Color::hsl(hue, 0.65, 0.55)
+}
+
+pub(crate) fn species_population_system(
+    cells: Query<&SpeciesId, With<Cell>>,
+    mut registry: ResMut<SpeciesRegistry>,
+) {
+    for species in registry.species.iter_mut() {
+        species.population = 0;
+    }
+    for id in cells.iter() {
+        if let Some(species) = registry.species.get_mut(id.0 as usize) {
+            species.population += 1;
+        }
+    }
+}
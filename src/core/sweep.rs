@@ -0,0 +1,227 @@
+use std::{
+    collections::BTreeMap,
+    error::Error,
+    fmt::Write as _,
+    fs,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    thread,
+};
+
+use serde::Deserialize;
+
+use crate::core::{
+    config::SimulationConfig,
+    headless::{run_headless, TickSample},
+};
+
+/// A parameter grid run `repetitions` times, read from a RON file:
+///
+/// ```ron
+/// (
+///     ticks: 600,
+///     repetitions: 3,
+///     base_seed: 42,
+///     base: (initial_cells: 10),
+///     parameters: {
+///         "seeds.step": [16, 32],
+///         "cells.radius": [10, 15],
+///     },
+/// )
+/// ```
+///
+/// Repetition `r` of every grid point uses seed `base_seed + r`, so grid points
+/// are compared under the same random conditions.
+#[derive(Deserialize, Clone, Debug)]
+pub struct SweepDefinition {
+    pub ticks: u64,
+    #[serde(default = "default_repetitions")]
+    pub repetitions: u32,
+    #[serde(default)]
+    pub base_seed: u64,
+    #[serde(default)]
+    pub threads: Option<usize>,
+    #[serde(default)]
+    pub base: SimulationConfig,
+    #[serde(default)]
+    pub parameters: BTreeMap<String, Vec<f64>>,
+}
+
+fn default_repetitions() -> u32 {
+    1
+}
+
+/// One headless run of the sweep.
+#[derive(Clone, Debug)]
+pub struct SweepRun {
+    pub index: usize,
+    pub repetition: u32,
+    pub parameters: Vec<(String, f64)>,
+    pub config: SimulationConfig,
+}
+
+struct RunSummary {
+    final_cells: usize,
+    final_predators: usize,
+    final_seeds: usize,
+    peak_cells: usize,
+    mean_cells: f64,
+}
+
+impl SweepDefinition {
+    /// Reads a definition, turning down a `base` that `set_parameter` would not accept.
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let text = fs::read_to_string(path)?;
+        let definition: Self = ron::from_str(&text)?;
+        definition.base.validate()?;
+        Ok(definition)
+    }
+
+    /// Expands the grid into the list of runs, in a stable order.
+    pub fn runs(&self) -> Result<Vec<SweepRun>, String> {
+        let mut grid: Vec<Vec<(String, f64)>> = vec![Vec::new()];
+        for (name, values) in &self.parameters {
+            if values.is_empty() {
+                return Err(format!("parameter `{name}` has no values"));
+            }
+            grid = grid
+                .into_iter()
+                .flat_map(|point| {
+                    values.iter().map(move |&value| {
+                        let mut point = point.clone();
+                        point.push((name.clone(), value));
+                        point
+                    })
+                })
+                .collect();
+        }
+
+        let mut runs = Vec::with_capacity(grid.len() * self.repetitions as usize);
+        for point in grid {
+            let mut config = self.base.clone();
+            for (name, value) in &point {
+                config.set_parameter(name, *value)?;
+            }
+            for repetition in 0..self.repetitions {
+                let mut config = config.clone();
+                config.rng_seed = self.base_seed.wrapping_add(repetition as u64);
+                runs.push(SweepRun {
+                    index: runs.len(),
+                    repetition,
+                    parameters: point.clone(),
+                    config,
+                });
+            }
+        }
+        Ok(runs)
+    }
+}
+
+/// Executes every run of `definition` on a pool of worker threads and writes
+/// `run_<index>.csv` per run, the index zero-padded to four digits as in
+/// `run_0007.csv`, plus `summary.csv` into `out_dir`. `progress` gets the
+/// number of finished runs and the total after each run.
+pub fn run_sweep(
+    definition: &SweepDefinition,
+    out_dir: &Path,
+    progress: impl Fn(usize, usize) + Sync,
+) -> Result<PathBuf, Box<dyn Error>> {
+    let runs = definition.runs()?;
+    fs::create_dir_all(out_dir)?;
+
+    let threads = definition
+        .threads
+        .or_else(|| thread::available_parallelism().ok().map(|n| n.get()))
+        .unwrap_or(1)
+        .clamp(1, runs.len().max(1));
+
+    let next_run = AtomicUsize::new(0);
+    let finished = AtomicUsize::new(0);
+    let summaries: Mutex<Vec<Option<RunSummary>>> = Mutex::new((0..runs.len()).map(|_| None).collect());
+    let first_error: Mutex<Option<String>> = Mutex::new(None);
+
+    thread::scope(|scope| {
+        for _ in 0..threads {
+            scope.spawn(|| loop {
+                let index = next_run.fetch_add(1, Ordering::Relaxed);
+                let Some(run) = runs.get(index) else { break };
+
+                let samples = run_headless(run.config.clone(), definition.ticks);
+                let path = out_dir.join(format!("run_{:04}.csv", run.index));
+                if let Err(err) = fs::write(&path, run_csv(&samples)) {
+                    first_error
+                        .lock()
+                        .unwrap()
+                        .get_or_insert(format!("{}: {err}", path.display()));
+                }
+                summaries.lock().unwrap()[index] = Some(summarize(&samples));
+                progress(finished.fetch_add(1, Ordering::Relaxed) + 1, runs.len());
+            });
+        }
+    });
+
+    if let Some(err) = first_error.into_inner().unwrap() {
+        return Err(err.into());
+    }
+
+    let summaries = summaries.into_inner().unwrap();
+    let summary_path = out_dir.join("summary.csv");
+    fs::write(&summary_path, summary_csv(definition, &runs, &summaries))?;
+    Ok(summary_path)
+}
+
+fn summarize(samples: &[TickSample]) -> RunSummary {
+    let last = samples.last();
+    RunSummary {
+        final_cells: last.map_or(0, |s| s.cells),
+        final_predators: last.map_or(0, |s| s.predators),
+        final_seeds: last.map_or(0, |s| s.seeds),
+        peak_cells: samples.iter().map(|s| s.cells).max().unwrap_or(0),
+        mean_cells: if samples.is_empty() {
+            0.
+        } else {
+            samples.iter().map(|s| s.cells as f64).sum::<f64>() / samples.len() as f64
+        },
+    }
+}
+
+fn run_csv(samples: &[TickSample]) -> String {
+    let mut csv = String::from("tick,cells,predators,seeds\n");
+    for sample in samples {
+        let _ = writeln!(
+            csv,
+            "{},{},{},{}",
+            sample.tick, sample.cells, sample.predators, sample.seeds
+        );
+    }
+    csv
+}
+
+fn summary_csv(definition: &SweepDefinition, runs: &[SweepRun], summaries: &[Option<RunSummary>]) -> String {
+    let mut csv = String::from("run,repetition,rng_seed");
+    for name in definition.parameters.keys() {
+        let _ = write!(csv, ",{name}");
+    }
+    csv.push_str(",final_cells,final_predators,final_seeds,peak_cells,mean_cells\n");
+
+    for (run, summary) in runs.iter().zip(summaries) {
+        let Some(summary) = summary else { continue };
+        let _ = write!(csv, "{},{},{}", run.index, run.repetition, run.config.rng_seed);
+        for (_, value) in &run.parameters {
+            let _ = write!(csv, ",{value}");
+        }
+        let _ = writeln!(
+            csv,
+            ",{},{},{},{},{:.3}",
+            summary.final_cells,
+            summary.final_predators,
+            summary.final_seeds,
+            summary.peak_cells,
+            summary.mean_cells
+        );
+    }
+    csv
+}
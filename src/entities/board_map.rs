@@ -0,0 +1,95 @@
+use std::fs;
+
+use bevy::{
+    prelude::*,
+    render::{
+        render_asset::RenderAssetUsages,
+        render_resource::{Extent3d, TextureDimension, TextureFormat},
+        texture::{CompressedImageFormats, ImageSampler, ImageType},
+    },
+};
+
+/// Pixels with at least this alpha are walkable.
+const WALKABLE_ALPHA: u8 = 128;
+
+/// Board shape and seed fertility read from an image stretched over the board rect.
+/// Alpha marks the walkable area and green the fertility.
+pub struct BoardMap {
+    width: usize,
+    height: usize,
+    walkable: Vec<bool>,
+    fertility: Vec<f32>,
+}
+
+impl BoardMap {
+    pub fn load(path: &str) -> Result<Self, String> {
+        let bytes = fs::read(path).map_err(|err| format!("{path}: {err}"))?;
+        let image = Image::from_buffer(
+            &bytes,
+            ImageType::Extension("png"),
+            CompressedImageFormats::NONE,
+            // Only the sRGB flavour converts to RGBA, the bytes are left as they are.
+            true,
+            ImageSampler::Default,
+            RenderAssetUsages::default(),
+        )
+        .map_err(|err| format!("{path}: {err}"))?
+        .convert(TextureFormat::Rgba8UnormSrgb)
+        .ok_or_else(|| format!("{path}: unsupported pixel format"))?;
+
+        let (width, height) = (image.width() as usize, image.height() as usize);
+        let pixels = image.data.chunks_exact(4);
+        Ok(Self {
+            width,
+            height,
+            walkable: pixels.clone().map(|pixel| pixel[3] >= WALKABLE_ALPHA).collect(),
+            fertility: pixels.map(|pixel| pixel[1] as f32 / 255.).collect(),
+        })
+    }
+
+    /// Index of the pixel under `position`, image rows going from the top of the board down.
+    fn pixel(&self, position: Vec2, board_rect: Rect) -> Option<usize> {
+        let uv = (position - board_rect.min) / board_rect.size();
+        if !(0. ..1.).contains(&uv.x) || !(0. ..1.).contains(&uv.y) {
+            return None;
+        }
+        let x = (uv.x * self.width as f32) as usize;
+        let y = ((1. - uv.y) * self.height as f32) as usize;
+        Some(y.min(self.height - 1) * self.width + x.min(self.width - 1))
+    }
+
+    pub fn is_walkable(&self, position: Vec2, board_rect: Rect) -> bool {
+        self.pixel(position, board_rect).is_some_and(|index| self.walkable[index])
+    }
+
+    /// Chance in `0..=1` that a seed is placed at `position` at start; `0` off the walkable area.
+    pub fn fertility_at(&self, position: Vec2, board_rect: Rect) -> f32 {
+        self.pixel(position, board_rect)
+            .filter(|index| self.walkable[*index])
+            .map_or(0., |index| self.fertility[index])
+    }
+
+    /// Mask drawn as the board: opaque where walkable, a little brighter where fertile.
+    pub fn to_image(&self) -> Image {
+        let data = self
+            .walkable
+            .iter()
+            .zip(&self.fertility)
+            .flat_map(|(&walkable, &fertility)| {
+                let shade = (255. * (0.75 + 0.25 * fertility)) as u8;
+                [shade, shade, shade, if walkable { 255 } else { 0 }]
+            })
+            .collect();
+        Image::new(
+            Extent3d {
+                width: self.width as u32,
+                height: self.height as u32,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            data,
+            TextureFormat::Rgba8UnormSrgb,
+            RenderAssetUsages::default(),
+        )
+    }
+}
@@ -1,4 +1,49 @@
 use bevy::prelude::*;
 
-#[derive(Component)]
-pub(crate) struct Cell {}
\ No newline at end of file
+#[derive(Component, Clone, Copy, Debug)]
+pub struct Cell {
+    pub energy: f32,
+}
+
+/// What a cell perceived this tick.
+#[derive(Component, Clone, Copy, Debug, Default)]
+pub struct CellSenses {
+    /// Index of the board under the cell, `None` once it has left every board.
+    pub board: Option<usize>,
+    /// Where the cell's food or its trail is.
+    pub target: Option<Vec2>,
+}
+
+/// Where the brain wants to go this tick, as a unit vector or zero.
+#[derive(Component, Clone, Copy, Debug, Default)]
+pub struct CellIntent {
+    pub direction: Vec3,
+}
+
+/// Marks cells that hunt other cells instead of eating seeds.
+#[derive(Component, Clone, Copy, Debug, Default)]
+pub struct Predator;
+
+/// Identity of a cell that stays unique for the whole run, unlike `Entity` which gets recycled.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct CellId(pub u64);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DeathCause {
+    LeftBoard,
+    Starvation,
+    Predation,
+    /// Killed on the user's command.
+    Killed,
+}
+
+impl DeathCause {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DeathCause::LeftBoard => "left board",
+            DeathCause::Starvation => "starvation",
+            DeathCause::Predation => "eaten by predator",
+            DeathCause::Killed => "killed",
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,21 @@
+use bevy_rapier2d::geometry::{CollisionGroups, Group};
+
+pub const SEEDS_GROUP: Group = Group::GROUP_1;
+pub const CELLS_GROUP: Group = Group::GROUP_2;
+pub const PREDATORS_GROUP: Group = Group::GROUP_3;
+pub const CORPSES_GROUP: Group = Group::GROUP_4;
+pub const OBSTACLES_GROUP: Group = Group::GROUP_5;
+
+/// Seeds push each other, bounce off obstacles and get eaten by cells; predators and corpses pass over them.
+pub const SEED_COLLISION_GROUPS: CollisionGroups =
+    CollisionGroups::new(SEEDS_GROUP, SEEDS_GROUP.union(CELLS_GROUP).union(OBSTACLES_GROUP));
+pub const CELL_COLLISION_GROUPS: CollisionGroups =
+    CollisionGroups::new(CELLS_GROUP, Group::ALL.difference(CORPSES_GROUP));
+/// Predators only touch their prey and the corpses they scavenge.
+pub const PREDATOR_COLLISION_GROUPS: CollisionGroups =
+    CollisionGroups::new(PREDATORS_GROUP, CELLS_GROUP.union(CORPSES_GROUP));
+pub const CORPSE_COLLISION_GROUPS: CollisionGroups =
+    CollisionGroups::new(CORPSES_GROUP, PREDATORS_GROUP);
+/// Cells have no rigid body, so they are kept out of obstacles when they move instead.
+pub const OBSTACLE_COLLISION_GROUPS: CollisionGroups =
+    CollisionGroups::new(OBSTACLES_GROUP, SEEDS_GROUP);
@@ -0,0 +1,10 @@
+use bevy::prelude::*;
+
+/// Remains of a dead cell; its energy slowly turns back into seeds.
+#[derive(Component, Clone, Copy, Debug)]
+pub(crate) struct Corpse {
+    pub energy: f32,
+    /// Energy already decomposed but not yet enough for a whole seed.
+    pub decomposed: f32,
+    pub radius: f32,
+}
\ No newline at end of file
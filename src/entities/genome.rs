@@ -0,0 +1,44 @@
+use bevy::prelude::*;
+use rand::Rng;
+
+/// Heritable traits of a cell, copied with small mutations on division.
+#[derive(Component, Clone, Copy, Debug, PartialEq)]
+pub struct Genome {
+    pub radius: f32,
+    pub speed: f32,
+    pub sensor_range: f32,
+}
+
+impl Genome {
+    /// Euclidean distance over the relative difference of every trait, so traits
+    /// with large absolute values do not dominate.
+    pub fn distance(&self, other: &Genome) -> f32 {
+        let pairs = [
+            (self.radius, other.radius),
+            (self.speed, other.speed),
+            (self.sensor_range, other.sensor_range),
+        ];
+        pairs
+            .iter()
+            .map(|&(a, b)| {
+                let scale = a.abs().max(b.abs());
+                if scale > 0. { (a - b) / scale } else { 0. }
+            })
+            .map(|d| d * d)
+            .sum::<f32>()
+            .sqrt()
+    }
+
+    /// Scales every trait by a random factor in `1 ± rate`.
+    pub fn mutated(&self, rng: &mut impl Rng, rate: f32) -> Genome {
+        let mut mutate = |value: f32, min: f32| {
+            let factor = if rate > 0. { 1. + rng.gen_range(-rate..=rate) } else { 1. };
+            (value * factor).max(min)
+        };
+        Genome {
+            radius: mutate(self.radius, 1.),
+            speed: mutate(self.speed, 0.05),
+            sensor_range: mutate(self.sensor_range, 0.),
+        }
+    }
+}
@@ -1,3 +1,9 @@
-pub(crate) mod cells;
-pub(crate) mod world_board;
-pub(crate) mod seed;
\ No newline at end of file
+pub mod board_map;
+pub mod cells;
+pub mod collision_groups;
+pub mod corpse;
+pub mod genome;
+pub mod plant;
+pub mod terrain;
+pub mod world_board;
+pub mod seed;
\ No newline at end of file
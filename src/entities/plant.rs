@@ -0,0 +1,9 @@
+use bevy::prelude::*;
+
+/// Stationary producer living on light and dropping seeds around itself.
+#[derive(Component, Clone, Copy, Debug)]
+pub(crate) struct Plant {
+    pub energy: f32,
+    /// Counts up to `SimulationConfig::plant_seed_interval`.
+    pub seed_progress: f32,
+}
\ No newline at end of file
@@ -1,4 +1,4 @@
 use bevy::prelude::*;
 
 #[derive(Component)]
-pub(crate) struct Seed {}
\ No newline at end of file
+pub struct Seed {}
\ No newline at end of file
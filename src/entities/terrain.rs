@@ -0,0 +1,130 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Static shape cells cannot pass and seeds bounce off, in world coordinates.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum ObstacleShape {
+    Rectangle { min: [f32; 2], max: [f32; 2] },
+    Circle { center: [f32; 2], radius: f32 },
+    /// Wall of the given width along the points.
+    Polyline { points: Vec<[f32; 2]>, width: f32 },
+}
+
+impl ObstacleShape {
+    /// Distance from `position` to the edge of the shape, `0` inside it.
+    pub fn distance_to(&self, position: Vec2) -> f32 {
+        match self {
+            ObstacleShape::Rectangle { min, max } => {
+                let rect = Rect::from_corners(Vec2::from(*min), Vec2::from(*max));
+                (position - position.clamp(rect.min, rect.max)).length()
+            }
+            ObstacleShape::Circle { center, radius } => {
+                (position.distance(Vec2::from(*center)) - radius).max(0.)
+            }
+            ObstacleShape::Polyline { points, width } => {
+                let centerline = match points.as_slice() {
+                    [] => f32::INFINITY,
+                    [point] => position.distance(Vec2::from(*point)),
+                    _ => points
+                        .windows(2)
+                        .map(|segment| {
+                            distance_to_segment(position, Vec2::from(segment[0]), Vec2::from(segment[1]))
+                        })
+                        .fold(f32::INFINITY, f32::min),
+                };
+                (centerline - width / 2.).max(0.)
+            }
+        }
+    }
+}
+
+pub fn distance_to_segment(position: Vec2, start: Vec2, end: Vec2) -> f32 {
+    let segment = end - start;
+    let length_squared = segment.length_squared();
+    if length_squared <= f32::EPSILON {
+        return position.distance(start);
+    }
+    let t = ((position - start).dot(segment) / length_squared).clamp(0., 1.);
+    position.distance(start + segment * t)
+}
+
+/// Area where moving is slower and more expensive.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TerrainRegion {
+    pub min: [f32; 2],
+    pub max: [f32; 2],
+    /// Divides the speed and multiplies the energy cells spend moving inside.
+    pub move_cost: f32,
+}
+
+impl TerrainRegion {
+    pub fn rect(&self) -> Rect {
+        Rect::from_corners(Vec2::from(self.min), Vec2::from(self.max))
+    }
+
+    /// Turns down costs that would stop cells dead, throw them across the board
+    /// or turn them around.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.move_cost.is_finite() && self.move_cost > 0. {
+            Ok(())
+        } else {
+            Err(format!("terrain `move_cost` must be positive, got {}", self.move_cost))
+        }
+    }
+}
+
+/// Obstacles and terrain placed on the board, saved as the world file.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WorldLayout {
+    pub obstacles: Vec<ObstacleShape>,
+    pub terrain: Vec<TerrainRegion>,
+}
+
+impl WorldLayout {
+    /// Whether a circle of `radius` at `position` overlaps an obstacle.
+    pub fn blocks(&self, position: Vec2, radius: f32) -> bool {
+        self.obstacles
+            .iter()
+            .any(|obstacle| obstacle.distance_to(position) <= radius)
+    }
+
+    /// Product of the movement costs of the regions under `position`, `1` outside all of them.
+    pub fn move_cost(&self, position: Vec2) -> f32 {
+        self.terrain
+            .iter()
+            .filter(|region| region.rect().contains(position))
+            .map(|region| region.move_cost)
+            .product()
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        self.terrain.iter().try_for_each(TerrainRegion::validate)
+    }
+
+    /// Removes the topmost obstacle, or else terrain region, under `position`.
+    pub fn erase_at(&mut self, position: Vec2) {
+        // Obstacles sit on top of terrain, so they go first.
+        if let Some(index) = self
+            .obstacles
+            .iter()
+            .rposition(|obstacle| obstacle.distance_to(position) <= 0.)
+        {
+            self.obstacles.remove(index);
+        } else if let Some(index) = self
+            .terrain
+            .iter()
+            .rposition(|region| region.rect().contains(position))
+        {
+            self.terrain.remove(index);
+        }
+    }
+}
+
+/// Entity drawing and colliding for one of `WorldLayout::obstacles`.
+#[derive(Component)]
+pub struct Obstacle;
+
+/// Entity drawing one of `WorldLayout::terrain`.
+#[derive(Component)]
+pub struct TerrainPatch;
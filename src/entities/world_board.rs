@@ -2,11 +2,40 @@ use bevy::{
     prelude::*,
 };
 
+use crate::entities::board_map::BoardMap;
+
 #[derive(Component)]
 pub struct WorldBoard {
     pub(crate) settings: BoardSettings,
 }
 
 pub struct BoardSettings {
+    pub(crate) name: String,
     pub(crate) rect: Rect,
-}
\ No newline at end of file
+    /// Shape and fertility of the board, the whole rect is walkable without it.
+    pub(crate) map: Option<BoardMap>,
+    pub(crate) seeds_grid: bool,
+    pub(crate) seeds_step: f32,
+    pub(crate) initial_cells: u32,
+    pub(crate) initial_predators: u32,
+    pub(crate) initial_plants: u32,
+}
+
+impl WorldBoard {
+    /// Whether `position` is on the walkable part of the board.
+    pub fn contains(&self, position: Vec2) -> bool {
+        self.settings.rect.contains(position)
+            && self
+                .settings
+                .map
+                .as_ref()
+                .map_or(true, |map| map.is_walkable(position, self.settings.rect))
+    }
+}
+
+/// Index of the board whose rect holds `position`; boards do not overlap.
+pub fn board_index_at<'a>(boards: impl IntoIterator<Item = &'a WorldBoard>, position: Vec2) -> Option<usize> {
+    boards
+        .into_iter()
+        .position(|board| board.settings.rect.contains(position))
+}
@@ -0,0 +1,9 @@
+//! Cells, predators and plants evolving on 2D boards, as Bevy plugins.
+//! The `simulation` binary runs them in a window, in sweeps or as a benchmark.
+
+pub mod actions;
+pub mod cameras;
+pub mod core;
+pub mod entities;
+pub mod ui;
+pub mod utils;
@@ -1,61 +1,209 @@
+use std::{path::PathBuf, process::ExitCode, time::Instant};
+
 use bevy::{
+    diagnostic::LogDiagnosticsPlugin,
+    log::LogPlugin,
     prelude::*,
     window::{PresentMode, Window, WindowPlugin},
 };
-use bevy_rapier2d::plugin::{NoUserData, RapierConfiguration, RapierPhysicsPlugin};
 
-use crate::{
-    actions::{
-        board_plugin::*,
-        cells_plugin::*,
-        seeds_plugin::*,
-    },
+use simulation::{
+    actions::input_plugin::*,
     cameras::main_camera::*,
+    core::{
+        config::SimulationConfig,
+        headless::{run_headless, run_headless_replay, TickSample},
+        network::BrainBackend,
+        replay::*,
+        simulation::*,
+        sweep::*,
+    },
     ui::{
         debug::*,
+        field_overlay::*,
         info_board::*,
+        lineage_view::*,
+        log_console::capture_logs,
+        world_editor::*,
     },
 };
 
-mod cameras;
-mod ui;
-mod core;
-mod actions;
-mod entities;
-mod utils;
-
-
-fn main() {
-    App::new()
-        .add_plugins(DefaultPlugins.set(WindowPlugin {
-            primary_window: Some(Window {
-                title: "Game_cap!".into(),
-                resolution: (900., 600.).into(),
-                present_mode: PresentMode::AutoVsync,
-                prevent_default_event_handling: false,
-                ..default()
-            }),
-            ..default()
-        }))
-        .add_systems(Startup, setup_system)
-        .add_plugins(RapierPhysicsPlugin::<NoUserData>::default())
+const USAGE: &str = "usage: simulation [sweep <definition.ron> [--out <dir>] | benchmark [--headless <ticks> | --scaling <ticks>] [--batched] | replay <replay.ron> [--headless]]";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.first().map(String::as_str) {
+        None => {
+            run_app(SimulationConfig::default(), None, false);
+            ExitCode::SUCCESS
+        }
+        Some("sweep") => sweep_command(&args[1..]),
+        Some("replay") => replay_command(&args[1..]),
+        Some("benchmark") => benchmark_command(&args[1..]),
+        Some(_) => {
+            eprintln!("{USAGE}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run_app(config: SimulationConfig, replay: Option<Replay>, log_frame_times: bool) {
+    let mut app = App::new();
+    if let Some(replay) = replay {
+        app.insert_resource(ReplayState::playing(replay));
+    }
+    app.insert_resource(config)
+        .add_plugins(
+            DefaultPlugins
+                .set(WindowPlugin {
+                    primary_window: Some(Window {
+                        title: "Game_cap!".into(),
+                        resolution: (900., 600.).into(),
+                        present_mode: PresentMode::AutoVsync,
+                        prevent_default_event_handling: false,
+                        ..default()
+                    }),
+                    ..default()
+                })
+                .set(LogPlugin {
+                    update_subscriber: Some(capture_logs),
+                    ..default()
+                }),
+        )
         .add_plugins((
             GameCapCameraPlugin,
             DebugPlugin,
+            FieldOverlayPlugin,
             InfoBoardPlugin,
-            WorldBoardPlugin,
-            CellsPlugin,
-            SeedsPlugin,
-        ))
-        .run();
+            LineageViewPlugin,
+            WorldEditorPlugin,
+            SimulationPlugin,
+            InputActionsPlugin,
+        ));
+    if log_frame_times {
+        app.add_plugins(LogDiagnosticsPlugin::default());
+    }
+    app.run();
+}
+
+/// The crowded scene from `SimulationConfig::benchmark`, in a window with frame
+/// times logged, or headless for a number of ticks to time the simulation alone.
+fn benchmark_command(args: &[String]) -> ExitCode {
+    match args {
+        [] => {
+            run_app(SimulationConfig::benchmark(), None, true);
+            ExitCode::SUCCESS
+        }
+        [flag, ticks, backend @ ..]
+            if (flag == "--headless" || flag == "--scaling") && matches!(backend, [] | [_]) =>
+        {
+            let brain_backend = match backend {
+                [] => BrainBackend::Scalar,
+                [batched] if batched == "--batched" => BrainBackend::Batched,
+                _ => {
+                    eprintln!("{USAGE}");
+                    return ExitCode::FAILURE;
+                }
+            };
+            let Ok(ticks) = ticks.parse::<u64>() else {
+                eprintln!("{USAGE}");
+                return ExitCode::FAILURE;
+            };
+            let populations: &[u32] = if flag == "--headless" { &[10_000] } else { &SCALING_POPULATIONS };
+            for &cells in populations {
+                let config = SimulationConfig {
+                    brain_backend,
+                    ..SimulationConfig::benchmark_with_cells(cells)
+                };
+                benchmark_headless(config, ticks);
+            }
+            ExitCode::SUCCESS
+        }
+        _ => {
+            eprintln!("{USAGE}");
+            ExitCode::FAILURE
+        }
+    }
 }
 
-fn setup_system(
-    mut commands: Commands,
-) {
-    commands.insert_resource(RapierConfiguration {
-        gravity: Vec2::new(0., 0.),
-        ..Default::default()
-    });
+/// Populations compared by `benchmark --scaling`.
+const SCALING_POPULATIONS: [u32; 3] = [1_000, 10_000, 50_000];
+
+/// Times `ticks` headless ticks of `config` and prints the throughput.
+fn benchmark_headless(config: SimulationConfig, ticks: u64) {
+    let population = config.initial_cells;
+    let started = Instant::now();
+    let samples = run_headless(config, ticks);
+    let elapsed = started.elapsed();
+    if let (Some(first), Some(last)) = (samples.first(), samples.last()) {
+        let cell_ticks: f64 = samples.iter().map(|sample| sample.cells as f64).sum();
+        println!(
+            "benchmark {population} cells: {ticks} ticks in {:.2?}, {:.2} ms per tick, {:.0} cell ticks/s, cells {} -> {}, seeds {} -> {}",
+            elapsed,
+            elapsed.as_secs_f64() * 1000. / ticks as f64,
+            cell_ticks / elapsed.as_secs_f64(),
+            first.cells,
+            last.cells,
+            first.seeds,
+            last.seeds,
+        );
+    }
 }
 
+/// Plays a recorded run in a window, or headless to its end printing the populations.
+fn replay_command(args: &[String]) -> ExitCode {
+    let (path, headless) = match args {
+        [path] => (PathBuf::from(path), false),
+        [path, flag] if flag == "--headless" => (PathBuf::from(path), true),
+        _ => {
+            eprintln!("{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let replay = match Replay::load(&path) {
+        Ok(replay) => replay,
+        Err(err) => {
+            eprintln!("replay: failed to load {}: {err}", path.display());
+            return ExitCode::FAILURE;
+        }
+    };
+    if !headless {
+        run_app(replay.config.clone(), Some(replay), false);
+        return ExitCode::SUCCESS;
+    }
+
+    let inputs = replay.inputs.len();
+    let samples = run_headless_replay(replay);
+    if let Some(TickSample { tick, cells, predators, seeds }) = samples.last() {
+        println!("replay: {inputs} inputs, tick {tick}: cells {cells}, predators {predators}, seeds {seeds}");
+    }
+    ExitCode::SUCCESS
+}
+
+fn sweep_command(args: &[String]) -> ExitCode {
+    let (definition_path, out_dir) = match args {
+        [path] => (PathBuf::from(path), PathBuf::from("sweep_results")),
+        [path, flag, out] if flag == "--out" => (PathBuf::from(path), PathBuf::from(out)),
+        _ => {
+            eprintln!("{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let result = SweepDefinition::load(&definition_path)
+        .and_then(|definition| {
+            run_sweep(&definition, &out_dir, |finished, total| {
+                println!("sweep: {finished} / {total} runs done");
+            })
+        });
+    match result {
+        Ok(summary_path) => {
+            println!("sweep: summary written to {}", summary_path.display());
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("sweep failed: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
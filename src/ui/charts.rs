@@ -0,0 +1,503 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    ops::RangeInclusive,
+};
+
+use bevy::prelude::*;
+use bevy_egui::egui;
+use egui_plot::{Bar, BarChart, GridMark, Legend, Line, Plot, PlotPoint, PlotPoints, Points, Polygon};
+
+use crate::{
+    core::{
+        metrics::{sim_seconds, ticks_in, WorldMetrics},
+        species::{SpeciesId, SpeciesRegistry},
+    },
+    entities::genome::Genome,
+    utils::{ring_buffer::RingBuffer, time_series::TimeSeries},
+};
+
+/// Most buckets drawn per plotted series.
+const PLOT_POINTS: usize = 300;
+
+/// A count the population chart can show.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Metric {
+    Seeds,
+    Plants,
+    Cells,
+    Predators,
+}
+
+impl Metric {
+    const ALL: [Metric; 4] = [Self::Seeds, Self::Plants, Self::Cells, Self::Predators];
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Seeds => "Seeds",
+            Self::Plants => "Plants",
+            Self::Cells => "Cells",
+            Self::Predators => "Predators",
+        }
+    }
+
+    fn color(self) -> egui::Color32 {
+        match self {
+            Self::Seeds => egui::Color32::from_rgb(214, 180, 70),
+            Self::Plants => egui::Color32::from_rgb(70, 170, 80),
+            Self::Cells => egui::Color32::from_rgb(80, 120, 220),
+            Self::Predators => egui::Color32::from_rgb(210, 70, 70),
+        }
+    }
+
+    fn series(self, metrics: &WorldMetrics) -> &TimeSeries {
+        match self {
+            Self::Seeds => &metrics.seeds_count,
+            Self::Plants => &metrics.plants_count,
+            Self::Cells => &metrics.cells_count,
+            Self::Predators => &metrics.predators_count,
+        }
+    }
+}
+
+/// How far back the charts reach.
+#[derive(Default, Clone, Copy, PartialEq)]
+enum PlotWindow {
+    #[default]
+    TenSeconds,
+    Minute,
+    TenMinutes,
+    WholeRun,
+}
+
+impl PlotWindow {
+    const ALL: [PlotWindow; 4] = [Self::TenSeconds, Self::Minute, Self::TenMinutes, Self::WholeRun];
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::TenSeconds => "10 s",
+            Self::Minute => "1 min",
+            Self::TenMinutes => "10 min",
+            Self::WholeRun => "Whole run",
+        }
+    }
+
+    fn ticks(self) -> u64 {
+        match self {
+            Self::TenSeconds => ticks_in(10.),
+            Self::Minute => ticks_in(60.),
+            Self::TenMinutes => ticks_in(600.),
+            Self::WholeRun => u64::MAX,
+        }
+    }
+}
+
+/// What the metric charts show, and where.
+pub(crate) struct ChartSettings {
+    window: PlotWindow,
+    shown: BTreeSet<Metric>,
+    per_board: bool,
+    species: bool,
+    /// Whether the charts are drawn in their own window instead of the side panel.
+    pub(crate) popped_out: bool,
+}
+
+impl Default for ChartSettings {
+    fn default() -> Self {
+        Self {
+            window: PlotWindow::default(),
+            shown: Metric::ALL.into_iter().collect(),
+            per_board: false,
+            species: true,
+            popped_out: false,
+        }
+    }
+}
+
+/// Window, metric chooser and pop-out toggle of the charts.
+pub(crate) fn chart_controls(ui: &mut egui::Ui, settings: &mut ChartSettings, boards: usize) {
+    ui.horizontal(|ui| {
+        egui::ComboBox::from_id_source("Plotted window")
+            .selected_text(settings.window.as_str())
+            .show_ui(ui, |ui| {
+                for option in PlotWindow::ALL {
+                    ui.selectable_value(&mut settings.window, option, option.as_str());
+                }
+            });
+        let pop_label = if settings.popped_out { "Dock" } else { "Pop out" };
+        if ui.button(pop_label).clicked() {
+            settings.popped_out = !settings.popped_out;
+        }
+    });
+    ui.horizontal_wrapped(|ui| {
+        for metric in Metric::ALL {
+            let mut shown = settings.shown.contains(&metric);
+            if ui.checkbox(&mut shown, metric.as_str()).changed() {
+                if shown {
+                    settings.shown.insert(metric);
+                } else {
+                    settings.shown.remove(&metric);
+                }
+            }
+        }
+        if boards > 1 {
+            ui.checkbox(&mut settings.per_board, "Per island");
+        }
+        ui.checkbox(&mut settings.species, "Species");
+    });
+}
+
+/// The chosen counts on one chart and the species on another, sharing their
+/// time axis, each `height` points high.
+pub(crate) fn metrics_charts(
+    ui: &mut egui::Ui,
+    settings: &ChartSettings,
+    metrics: &WorldMetrics,
+    registry: &SpeciesRegistry,
+    height: f32,
+) {
+    let end = metrics.cells_count.ticks().map_or(0, |ticks| *ticks.end());
+    let window = end.saturating_sub(settings.window.ticks().saturating_sub(1))..=end;
+
+    let mut lines = Vec::new();
+    for &metric in &settings.shown {
+        lines.extend(series_lines(metric.series(metrics), window.clone(), metric.as_str(), metric.color()));
+        if !settings.per_board || metrics.boards.len() < 2 {
+            continue;
+        }
+        for board in &metrics.boards {
+            let series = match metric {
+                Metric::Seeds => &board.seeds_count,
+                Metric::Cells => &board.cells_count,
+                Metric::Predators => &board.predators_count,
+                Metric::Plants => continue,
+            };
+            let name = format!("{} on {}", metric.as_str(), board.name);
+            lines.extend(series_lines(series, window.clone(), &name, metric.color().linear_multiply(0.6)));
+        }
+    }
+    time_chart("Population chart", height).show(ui, |plot_ui| {
+        for line in lines {
+            plot_ui.line(line);
+        }
+    });
+
+    if !settings.species {
+        return;
+    }
+    let (lines, bands) = stacked_species(metrics, registry, window);
+    time_chart("Species chart", height).show(ui, |plot_ui| {
+        for band in bands {
+            plot_ui.polygon(band);
+        }
+        for line in lines {
+            plot_ui.line(line);
+        }
+    });
+}
+
+/// Per-species populations stacked on top of each other: the top edge of every
+/// species as a line, and the band down to the species below it filled in.
+fn stacked_species(
+    metrics: &WorldMetrics,
+    registry: &SpeciesRegistry,
+    window: RangeInclusive<u64>,
+) -> (Vec<Line>, Vec<Polygon>) {
+    // Species are counted over different spans, so they are all read at the same ticks.
+    let (start, end) = (*window.start(), *window.end());
+    let step = ((end - start) / PLOT_POINTS as u64).max(1);
+    let ticks: Vec<u64> = (start..=end).step_by(step as usize).collect();
+
+    let mut below = vec![0.; ticks.len()];
+    let (mut lines, mut bands) = (Vec::new(), Vec::new());
+    for (species_id, history) in &metrics.species_count {
+        let buckets: Vec<_> = history.window(window.clone(), PLOT_POINTS).collect();
+        if buckets.is_empty() {
+            continue;
+        }
+        let count_at = |tick: u64| {
+            let index = buckets.partition_point(|bucket| bucket.end() < tick);
+            buckets.get(index).filter(|bucket| bucket.start <= tick).map_or(0., |bucket| bucket.mean())
+        };
+        let above: Vec<f64> = ticks.iter().zip(&below).map(|(tick, below)| below + count_at(*tick)).collect();
+
+        let species = registry.get(*species_id);
+        let color = egui_color(species.map_or(Color::GRAY, |species| species.color));
+        // Only living species get a legend entry, the rest stay as unnamed traces.
+        let name = species.filter(|species| species.population > 0).map(|_| species_name(*species_id));
+        let x = |index: usize| sim_seconds(ticks[index]);
+        // A quad per step, as the band as a whole need not be convex.
+        for index in 1..ticks.len() {
+            if above[index - 1] == below[index - 1] && above[index] == below[index] {
+                continue;
+            }
+            let quad = vec![
+                [x(index - 1), below[index - 1]],
+                [x(index), below[index]],
+                [x(index), above[index]],
+                [x(index - 1), above[index - 1]],
+            ];
+            let band = Polygon::new(quad).fill_color(color.linear_multiply(0.4)).stroke(egui::Stroke::NONE);
+            bands.push(match &name {
+                Some(name) => band.name(name),
+                None => band,
+            });
+        }
+        let points: PlotPoints = (0..ticks.len()).map(|index| [x(index), above[index]]).collect();
+        let line = Line::new(points).color(color);
+        lines.push(match &name {
+            Some(name) => line.name(name),
+            None => line,
+        });
+        below = above;
+    }
+    (lines, bands)
+}
+
+/// The mean of `series` over `window`, with its min and max as fainter lines
+/// of the same name once buckets span several ticks.
+fn series_lines(series: &TimeSeries, window: RangeInclusive<u64>, name: &str, color: egui::Color32) -> Vec<Line> {
+    let buckets: Vec<_> = series.window(window, PLOT_POINTS).collect();
+    let line = |value: fn(&_) -> f64| -> PlotPoints {
+        buckets
+            .iter()
+            .map(|bucket| [sim_seconds(bucket.start), value(bucket)])
+            .collect()
+    };
+    let mut lines = vec![Line::new(line(|bucket| bucket.mean())).color(color).name(name)];
+    if buckets.iter().any(|bucket| bucket.span > 1) {
+        let faint = color.linear_multiply(0.35);
+        lines.push(Line::new(line(|bucket| bucket.min)).color(faint).name(name));
+        lines.push(Line::new(line(|bucket| bucket.max)).color(faint).name(name));
+    }
+    lines
+}
+
+/// A chart against simulated time: autoscaled, zoomed and dragged along time,
+/// with a legend and the value under the pointer.
+fn time_chart(id: &str, height: f32) -> Plot {
+    Plot::new(id)
+        .height(height)
+        .legend(Legend::default())
+        .allow_scroll(false)
+        .allow_zoom([true, false])
+        .allow_drag([true, false])
+        .auto_bounds([true, true].into())
+        .link_axis("metrics charts", true, false)
+        .link_cursor("metrics charts", true, false)
+        .x_axis_label("Simulated time")
+        .x_axis_formatter(|mark: GridMark, _, _: &RangeInclusive<f64>| clock_time(mark.value))
+        .label_formatter(|name, point: &PlotPoint| {
+            let value = format!("{:.0} at {}", point.y, clock_time(point.x));
+            if name.is_empty() {
+                value
+            } else {
+                format!("{name}\n{value}")
+            }
+        })
+}
+
+/// A trait or state of a living cell the composition charts can show.
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum Trait {
+    Radius,
+    Speed,
+    SensorRange,
+    Energy,
+    /// Simulated seconds since birth.
+    Age,
+}
+
+impl Trait {
+    const ALL: [Trait; 5] = [Self::Radius, Self::Speed, Self::SensorRange, Self::Energy, Self::Age];
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Radius => "Size",
+            Self::Speed => "Speed",
+            Self::SensorRange => "Sensor range",
+            Self::Energy => "Energy",
+            Self::Age => "Age",
+        }
+    }
+
+    fn value(self, cell: &CellSample) -> f64 {
+        match self {
+            Self::Radius => cell.genome.radius as f64,
+            Self::Speed => cell.genome.speed as f64,
+            Self::SensorRange => cell.genome.sensor_range as f64,
+            Self::Energy => cell.energy as f64,
+            Self::Age => sim_seconds(cell.age),
+        }
+    }
+
+    fn chooser(ui: &mut egui::Ui, label: &str, selected: &mut Trait) {
+        egui::ComboBox::from_label(label)
+            .selected_text(selected.as_str())
+            .show_ui(ui, |ui| {
+                for option in Trait::ALL {
+                    ui.selectable_value(selected, option, option.as_str());
+                }
+            });
+    }
+}
+
+/// What the composition charts show.
+pub(crate) struct CompositionSettings {
+    histogram: Trait,
+    scatter: [Trait; 2],
+}
+
+impl Default for CompositionSettings {
+    fn default() -> Self {
+        Self {
+            histogram: Trait::Radius,
+            scatter: [Trait::Speed, Trait::SensorRange],
+        }
+    }
+}
+
+/// What the composition charts know of a living cell.
+pub(crate) struct CellSample {
+    pub(crate) genome: Genome,
+    pub(crate) energy: f32,
+    /// Ticks since birth.
+    pub(crate) age: u64,
+    pub(crate) species: SpeciesId,
+    pub(crate) predator: bool,
+}
+
+/// Bars of the histogram of the chosen trait.
+const HISTOGRAM_BINS: usize = 20;
+
+/// A histogram of one trait over `cells`, predators stacked on the others,
+/// and a scatter of two traits coloured by species.
+pub(crate) fn composition_charts(
+    ui: &mut egui::Ui,
+    settings: &mut CompositionSettings,
+    cells: &[CellSample],
+    registry: &SpeciesRegistry,
+) {
+    Trait::chooser(ui, "Histogram", &mut settings.histogram);
+    trait_histogram(ui, settings.histogram, cells);
+
+    ui.horizontal(|ui| {
+        let [x, y] = &mut settings.scatter;
+        Trait::chooser(ui, "x", x);
+        Trait::chooser(ui, "y", y);
+    });
+    trait_scatter(ui, settings.scatter, cells, registry);
+}
+
+fn trait_histogram(ui: &mut egui::Ui, shown: Trait, cells: &[CellSample]) {
+    let values = || cells.iter().map(|cell| shown.value(cell));
+    let min = values().fold(f64::INFINITY, f64::min);
+    let max = values().fold(f64::NEG_INFINITY, f64::max);
+    // A single value still gets a bar of some width.
+    let width = if max > min { (max - min) / HISTOGRAM_BINS as f64 } else { 1. };
+
+    let mut counts = [[0usize; HISTOGRAM_BINS]; 2];
+    for cell in cells {
+        let bin = (((shown.value(cell) - min) / width) as usize).min(HISTOGRAM_BINS - 1);
+        counts[cell.predator as usize][bin] += 1;
+    }
+    let chart = |counts: &[usize; HISTOGRAM_BINS], name: &str, color: egui::Color32| {
+        let bars = counts
+            .iter()
+            .enumerate()
+            .map(|(bin, count)| Bar::new(min + (bin as f64 + 0.5) * width, *count as f64).width(width))
+            .collect();
+        BarChart::new(bars).color(color).name(name)
+    };
+    let cells_chart = chart(&counts[0], "Cells", Metric::Cells.color());
+    let predators_chart = chart(&counts[1], "Predators", Metric::Predators.color()).stack_on(&[&cells_chart]);
+
+    Plot::new("Trait histogram")
+        .height(120.)
+        .legend(Legend::default())
+        .allow_scroll(false)
+        .allow_drag(false)
+        .allow_zoom(false)
+        .include_y(0.)
+        .x_axis_label(shown.as_str())
+        .show(ui, |plot_ui| {
+            if !cells.is_empty() {
+                plot_ui.bar_chart(cells_chart);
+                plot_ui.bar_chart(predators_chart);
+            }
+        });
+}
+
+fn trait_scatter(ui: &mut egui::Ui, [x, y]: [Trait; 2], cells: &[CellSample], registry: &SpeciesRegistry) {
+    let mut by_species: BTreeMap<SpeciesId, Vec<[f64; 2]>> = BTreeMap::new();
+    for cell in cells {
+        by_species
+            .entry(cell.species)
+            .or_default()
+            .push([x.value(cell), y.value(cell)]);
+    }
+
+    Plot::new("Trait scatter")
+        .height(160.)
+        .legend(Legend::default())
+        .allow_scroll(false)
+        .x_axis_label(x.as_str())
+        .y_axis_label(y.as_str())
+        .label_formatter(move |name, point| {
+            format!("{name}\n{}: {:.2}\n{}: {:.2}", x.as_str(), point.x, y.as_str(), point.y)
+        })
+        .show(ui, |plot_ui| {
+            for (species, points) in by_species {
+                let color = registry.get(species).map_or(Color::GRAY, |species| species.color);
+                plot_ui.points(
+                    Points::new(points)
+                        .radius(2.)
+                        .color(egui_color(color))
+                        .name(species_name(species)),
+                );
+            }
+        });
+}
+
+/// Frame rates of the last frames, newest on the right.
+pub(crate) fn frames_chart(ui: &mut egui::Ui, frames: &RingBuffer<f64>, id: &str) -> egui::Response {
+    let points: PlotPoints = frames
+        .iter()
+        .enumerate()
+        .map(|(frame, fps)| [frame as f64, *fps])
+        .collect();
+    let line = Line::new(points).color(egui::Color32::from_rgb(55, 55, 155));
+
+    Plot::new(id)
+        .height(64.0)
+        .show_axes([false, true])
+        .allow_scroll(false)
+        .allow_drag(false)
+        .allow_zoom(false)
+        .include_y(0.)
+        .label_formatter(|_, point| format!("{:.1} FPS", point.y))
+        .show(ui, |plot_ui| plot_ui.line(line))
+        .response
+}
+
+/// `seconds` as `m:ss`, or `h:mm:ss` past the hour.
+fn clock_time(seconds: f64) -> String {
+    if seconds < 0. {
+        return String::new();
+    }
+    let seconds = seconds.round() as u64;
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    if hours > 0 {
+        format!("{hours}:{minutes:02}:{seconds:02}")
+    } else {
+        format!("{minutes}:{seconds:02}")
+    }
+}
+
+fn species_name(id: SpeciesId) -> String {
+    format!("Species #{}", id.0)
+}
+
+pub(crate) fn egui_color(color: Color) -> egui::Color32 {
+    let [r, g, b, a] = color.as_rgba_u8();
+    egui::Color32::from_rgba_unmultiplied(r, g, b, a)
+}
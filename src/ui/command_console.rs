@@ -0,0 +1,287 @@
+use std::{f32::consts::PI, path::PathBuf};
+
+use bevy::prelude::*;
+use bevy_egui::egui;
+
+use crate::{
+    core::{
+        config::SimulationConfig,
+        replay::UserInput,
+        simulation::{SimulationPaused, SimulationTick},
+        species::SpeciesRegistry,
+    },
+    ui::{
+        log_console::{LogConsole, LogKind},
+        world_editor::save_world,
+    },
+};
+
+const COMMANDS: [&str; 7] = ["spawn", "set", "kill", "save", "pause", "resume", "help"];
+
+const HELP: &str = "spawn cell|predator <x> <y> [count=<n>], set <parameter> <value>, \
+kill species <id>, save <file.ron>, pause, resume, help";
+
+const SPAWN_USAGE: &str = "usage: spawn cell|predator <x> <y> [count=<n>]";
+
+/// Cells spawned by one command are laid out on a sunflower spiral this far apart.
+const SPAWN_SPACING: f32 = 12.;
+
+/// Most cells one command spawns; every one of them is a recorded input.
+pub const MAX_SPAWN_COUNT: u32 = 1000;
+
+/// Runs the lines typed into the console prompt.
+pub struct CommandConsolePlugin;
+
+impl Plugin for CommandConsolePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<CommandLine>()
+            .init_resource::<CommandConsole>()
+            .add_systems(Update, run_command_system);
+    }
+}
+
+/// A line submitted to the console.
+#[derive(Event, Clone, Debug)]
+pub struct CommandLine(pub String);
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Command {
+    Spawn { position: Vec2, predator: bool, count: u32 },
+    Set { name: String, value: f64 },
+    KillSpecies(u32),
+    /// Writes the world layout to a file.
+    Save(PathBuf),
+    Pause,
+    Resume,
+    Help,
+}
+
+impl Command {
+    pub fn parse(line: &str) -> Result<Command, String> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let number = |word: &str| word.parse::<f64>().map_err(|_| format!("`{word}` is not a number"));
+        match words.as_slice() {
+            ["spawn", kind @ ("cell" | "predator"), x, y, options @ ..] => {
+                let mut count = 1;
+                for option in options {
+                    let value = option
+                        .strip_prefix("count=")
+                        .ok_or_else(|| format!("unknown option `{option}`"))?;
+                    count = value.parse().map_err(|_| format!("`{value}` is not a count"))?;
+                }
+                if count > MAX_SPAWN_COUNT {
+                    return Err(format!("at most {MAX_SPAWN_COUNT} cells at once, {SPAWN_USAGE}"));
+                }
+                Ok(Command::Spawn {
+                    position: Vec2::new(number(x)? as f32, number(y)? as f32),
+                    predator: *kind == "predator",
+                    count,
+                })
+            }
+            ["spawn", ..] => Err(SPAWN_USAGE.to_string()),
+            ["set", name, value] => Ok(Command::Set {
+                name: name.to_string(),
+                value: number(value)?,
+            }),
+            ["set", ..] => Err("usage: set <parameter> <value>".to_string()),
+            ["kill", "species", id] => id
+                .parse()
+                .map(Command::KillSpecies)
+                .map_err(|_| format!("`{id}` is not a species id")),
+            ["kill", ..] => Err("usage: kill species <id>".to_string()),
+            ["save", path] => Ok(Command::Save(PathBuf::from(path))),
+            ["save", ..] => Err("usage: save <file.ron>".to_string()),
+            ["pause"] => Ok(Command::Pause),
+            ["resume"] => Ok(Command::Resume),
+            ["help"] => Ok(Command::Help),
+            [] => Err("empty command".to_string()),
+            [word, ..] => Err(format!("unknown command `{word}`, try `help`")),
+        }
+    }
+}
+
+/// Ways to go on with the last word of `line`, given the parameter names and
+/// living species there are.
+pub fn completions(line: &str, parameters: &[String], species: &[u32]) -> Vec<String> {
+    let mut words: Vec<&str> = line.split_whitespace().collect();
+    if line.is_empty() || line.ends_with(char::is_whitespace) {
+        words.push("");
+    }
+    let Some((last, before)) = words.split_last() else { return Vec::new() };
+    let candidates: Vec<String> = match before {
+        [] => COMMANDS.map(String::from).to_vec(),
+        ["spawn"] => vec!["cell".to_string(), "predator".to_string()],
+        ["spawn", _, _, _, ..] => vec!["count=".to_string()],
+        ["set"] => parameters.to_vec(),
+        ["kill"] => vec!["species".to_string()],
+        ["kill", "species"] => species.iter().map(u32::to_string).collect(),
+        _ => Vec::new(),
+    };
+    candidates
+        .into_iter()
+        .filter(|candidate| candidate.starts_with(last) && candidate != last)
+        .collect()
+}
+
+/// The prompt and the lines submitted before, oldest first.
+#[derive(Resource, Default)]
+pub struct CommandConsole {
+    input: String,
+    history: Vec<String>,
+    /// Entry of `history` shown in the prompt while browsing it.
+    browsing: Option<usize>,
+}
+
+/// A prompt with Tab completion and Up/Down history, sending submitted lines
+/// as [`CommandLine`] events.
+pub(crate) fn command_prompt_ui(
+    ui: &mut egui::Ui,
+    console: &mut CommandConsole,
+    parameters: &[String],
+    species: &[u32],
+    lines: &mut EventWriter<CommandLine>,
+) {
+    let id = egui::Id::new("command prompt");
+    let focused = ui.memory(|memory| memory.has_focus(id));
+    let (tab, up, down) = ui.input_mut(|input| {
+        let mut consume = |key| focused && input.consume_key(egui::Modifiers::NONE, key);
+        (consume(egui::Key::Tab), consume(egui::Key::ArrowUp), consume(egui::Key::ArrowDown))
+    });
+    let options = completions(&console.input, parameters, species);
+
+    if tab {
+        if let Some(completed) = complete(&console.input, &options) {
+            console.input = completed;
+        }
+    }
+    if up || down {
+        let last = console.history.len().checked_sub(1);
+        console.browsing = match (console.browsing, up) {
+            (None, true) => last,
+            (Some(index), true) => Some(index.saturating_sub(1)),
+            (Some(index), false) if Some(index) != last => Some(index + 1),
+            _ => None,
+        };
+        console.input = console.browsing.map_or(String::new(), |index| console.history[index].clone());
+    }
+
+    let response = ui
+        .horizontal(|ui| {
+            ui.label(">");
+            ui.add(
+                egui::TextEdit::singleline(&mut console.input)
+                    .id(id)
+                    .font(egui::TextStyle::Monospace)
+                    .hint_text("help")
+                    .desired_width(f32::INFINITY),
+            )
+        })
+        .inner;
+    if response.lost_focus() && ui.input(|input| input.key_pressed(egui::Key::Enter)) {
+        let line = console.input.trim().to_string();
+        if !line.is_empty() {
+            if console.history.last() != Some(&line) {
+                console.history.push(line.clone());
+            }
+            lines.send(CommandLine(line));
+        }
+        console.input.clear();
+        console.browsing = None;
+        response.request_focus();
+    }
+    if focused && !options.is_empty() {
+        ui.label(egui::RichText::new(options.join("  ")).monospace().weak());
+    }
+}
+
+/// `line` with its last word extended as far as every option agrees, and a
+/// space after it when only one option is left.
+fn complete(line: &str, options: &[String]) -> Option<String> {
+    let first = options.first()?;
+    let common = options.iter().fold(first.len(), |common, option| {
+        first
+            .chars()
+            .zip(option.chars())
+            .take(common)
+            .take_while(|(a, b)| a == b)
+            .count()
+    });
+    let start = line.rfind(char::is_whitespace).map_or(0, |index| index + 1);
+    let mut completed = format!("{}{}", &line[..start], &first[..common]);
+    if options.len() == 1 && !first.ends_with('=') {
+        completed.push(' ');
+    }
+    Some(completed)
+}
+
+fn run_command_system(
+    mut lines: EventReader<CommandLine>,
+    mut inputs: EventWriter<UserInput>,
+    mut paused: ResMut<SimulationPaused>,
+    mut log: ResMut<LogConsole>,
+    config: Res<SimulationConfig>,
+    registry: Res<SpeciesRegistry>,
+    tick: Res<SimulationTick>,
+) {
+    for CommandLine(line) in lines.read() {
+        log.push(LogKind::Command, tick.0, format!("> {line}"));
+        let reply = match Command::parse(line) {
+            Ok(command) => run_command(command, &mut inputs, &mut paused, &config, &registry),
+            Err(err) => err,
+        };
+        log.push(LogKind::Command, tick.0, reply);
+    }
+}
+
+/// Sends what changes the world as user inputs, so it is recorded and replayed.
+fn run_command(
+    command: Command,
+    inputs: &mut EventWriter<UserInput>,
+    paused: &mut SimulationPaused,
+    config: &SimulationConfig,
+    registry: &SpeciesRegistry,
+) -> String {
+    match command {
+        Command::Spawn { position, predator, count } => {
+            // Sunflower spiral: every cell gets about the same room around it.
+            let golden_angle = PI * (3. - 5f32.sqrt());
+            for index in 0..count {
+                let offset = Vec2::from_angle(index as f32 * golden_angle) * SPAWN_SPACING * (index as f32).sqrt();
+                inputs.send(UserInput::SpawnCell {
+                    position: (position + offset).into(),
+                    predator,
+                });
+            }
+            let kind = if predator { "predator" } else { "cell" };
+            format!("Spawning {count} {kind}(s) around ({}, {})", position.x, position.y)
+        }
+        Command::Set { name, value } => {
+            // Checked on a copy first, so a bad value is reported here and not recorded.
+            if let Err(err) = config.clone().set_parameter(&name, value) {
+                return err;
+            }
+            inputs.send(UserInput::SetParameter {
+                name: name.clone(),
+                value,
+            });
+            format!("Set {name} to {value}")
+        }
+        Command::KillSpecies(id) => match registry.living().find(|species| species.id.0 == id) {
+            Some(species) => {
+                inputs.send(UserInput::KillSpecies(id));
+                format!("Killing the {} cells of species #{id}", species.population)
+            }
+            None => format!("No living species #{id}"),
+        },
+        Command::Save(path) => save_world(&config.world, &path),
+        Command::Pause => {
+            paused.0 = true;
+            "Paused, `resume` to go on".to_string()
+        }
+        Command::Resume => {
+            paused.0 = false;
+            "Resumed".to_string()
+        }
+        Command::Help => HELP.to_string(),
+    }
+}
@@ -1,12 +1,11 @@
 use bevy::prelude::*;
-use bevy_inspector_egui::quick::WorldInspectorPlugin;
 
 pub struct DebugPlugin;
 
 impl Plugin for DebugPlugin {
-    fn build(&self, app: &mut App) {
+    fn build(&self, _app: &mut App) {
         if cfg!(debug_assertions) {
-            //app.add_plugins(WorldInspectorPlugin::new());
+            //_app.add_plugins(bevy_inspector_egui::quick::WorldInspectorPlugin::new());
         }
     }
 }
\ No newline at end of file
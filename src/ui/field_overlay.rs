@@ -0,0 +1,150 @@
+use bevy::{
+    prelude::*,
+    render::{
+        render_asset::RenderAssetUsages,
+        render_resource::{Extent3d, TextureDimension, TextureFormat},
+    },
+};
+use bevy_egui::{egui, EguiContexts};
+
+use crate::{core::fields::ScalarFields, entities::world_board::WorldBoard};
+
+const OVERLAY_COLOR: [u8; 3] = [255, 140, 40];
+
+pub struct FieldOverlayPlugin;
+
+impl Plugin for FieldOverlayPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FieldOverlaySettings>()
+            .add_systems(Update, (fields_window_system, field_overlay_system).chain());
+    }
+}
+
+#[derive(Resource)]
+struct FieldOverlaySettings {
+    /// Name of the layer drawn over the board, if any.
+    layer: Option<String>,
+    opacity: f32,
+}
+
+impl Default for FieldOverlaySettings {
+    fn default() -> Self {
+        Self {
+            layer: None,
+            opacity: 0.6,
+        }
+    }
+}
+
+#[derive(Component)]
+struct FieldOverlay {
+    board: Entity,
+}
+
+fn fields_window_system(
+    mut contexts: EguiContexts,
+    mut settings: ResMut<FieldOverlaySettings>,
+    fields: Query<&ScalarFields>,
+) {
+    // Every board carries the same layers.
+    let Some(fields) = fields.iter().next() else { return };
+    let ctx = contexts.ctx_mut();
+
+    egui::Window::new("Fields")
+        .default_open(false)
+        .show(ctx, |ui| {
+            let selected = settings.layer.clone().unwrap_or_else(|| "None".to_string());
+            egui::ComboBox::from_label("Overlay")
+                .selected_text(selected)
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut settings.layer, None, "None");
+                    for name in fields.layer_names() {
+                        ui.selectable_value(&mut settings.layer, Some(name.to_string()), name);
+                    }
+                });
+            ui.add(egui::Slider::new(&mut settings.opacity, 0. ..=1.).text("Opacity"));
+        });
+}
+
+/// Keeps a translucent texture of the selected layer over every board,
+/// brighter where the layer is higher relative to its maximum on that board.
+fn field_overlay_system(
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+    settings: Res<FieldOverlaySettings>,
+    boards: Query<(&WorldBoard, &ScalarFields, Entity)>,
+    mut overlays: Query<(&FieldOverlay, &Handle<Image>, &mut Visibility)>,
+    stale: Query<(&FieldOverlay, Entity)>,
+) {
+    // Boards go away when the world restarts, their overlays with them.
+    for (overlay, entity) in stale.iter() {
+        if !boards.contains(overlay.board) {
+            commands.entity(entity).despawn();
+        }
+    }
+    for (board, fields, board_entity) in boards.iter() {
+        let overlay = overlays
+            .iter_mut()
+            .find(|(overlay, ..)| overlay.board == board_entity);
+        let Some((_, handle, mut visibility)) = overlay else {
+            spawn_overlay(&mut commands, &mut images, board, fields, board_entity);
+            continue;
+        };
+
+        let Some(layer) = settings.layer.as_deref().and_then(|name| fields.layer(name)) else {
+            *visibility = Visibility::Hidden;
+            continue;
+        };
+        *visibility = Visibility::Visible;
+
+        let Some(image) = images.get_mut(handle) else { continue };
+        let values = fields.values(layer);
+        let max = values.iter().copied().fold(0., f32::max);
+        let (width, height) = fields.size();
+        for y in 0..height {
+            // Field rows start at the bottom of the board, image rows at the top.
+            let row = height - 1 - y;
+            for x in 0..width {
+                let level = if max > 0. { (values[y * width + x] / max).clamp(0., 1.) } else { 0. };
+                let pixel = (row * width + x) * 4;
+                image.data[pixel..pixel + 3].copy_from_slice(&OVERLAY_COLOR);
+                image.data[pixel + 3] = (level * settings.opacity * 255.) as u8;
+            }
+        }
+    }
+}
+
+fn spawn_overlay(
+    commands: &mut Commands,
+    images: &mut Assets<Image>,
+    board: &WorldBoard,
+    fields: &ScalarFields,
+    board_entity: Entity,
+) {
+    let (width, height) = fields.size();
+    let image = Image::new_fill(
+        Extent3d {
+            width: width as u32,
+            height: height as u32,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &[0, 0, 0, 0],
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    );
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                custom_size: Some(board.settings.rect.size()),
+                ..default()
+            },
+            texture: images.add(image),
+            // Over the board, under everything living on it.
+            transform: Transform::from_translation(board.settings.rect.center().extend(0.2)),
+            visibility: Visibility::Hidden,
+            ..default()
+        },
+        FieldOverlay { board: board_entity },
+    ));
+}
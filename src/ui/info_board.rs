@@ -1,24 +1,44 @@
+use std::path::Path;
+
 use bevy::{
     diagnostic::{DiagnosticsStore, FrameTimeDiagnosticsPlugin},
     prelude::*,
 };
-use egui_plot::{Line, Plot, PlotPoints};
-use egui::{
-    Rgba,
-};
 
 use crate::cameras::main_camera::GameCapCamera;
-use crate::utils::ring_buffer::RingBuffer;
+use crate::core::clock::WorldClock;
+use crate::core::config::SimulationConfig;
+use crate::core::lineage::LineageRecords;
+use crate::core::metrics::WorldMetrics;
+use crate::core::replay::{ReplayMode, ReplayState, UserInput};
+use crate::core::simulation::{SimulationPaused, SimulationTick};
+use crate::core::species::{SpeciesId, SpeciesRegistry};
+use crate::entities::cells::{Cell, CellId, Predator};
+use crate::entities::genome::Genome;
+use crate::ui::charts::*;
+use crate::ui::command_console::{command_prompt_ui, CommandConsole, CommandConsolePlugin, CommandLine};
+use crate::ui::log_console::{log_console_ui, LogConsole, LogConsolePlugin};
+use crate::ui::parameters_panel::{parameters_panel_ui, ParameterPanel};
+use crate::utils::ring_buffer::RollingStats;
 use bevy_egui::{egui, EguiContexts, EguiPlugin};
 
+const REPLAY_PATH: &str = "replay.ron";
+/// Ticks the rewind and fast-forward buttons jump by.
+const REWIND_TICKS: u64 = 300;
+
 #[derive(Default, Resource)]
 pub(crate) struct InfoBoardSettings {
     occupied_screen_space: OccupiedScreenSpace,
-    pub world_metrics: WorldMetrics,
     show_logs: bool,
     world_cursor_position: Vec3,
     window_cursor_position: Vec2,
-    frames: RingBuffer<f64>,
+    frames: RollingStats,
+    charts: ChartSettings,
+    composition: CompositionSettings,
+    parameters: ParameterPanel,
+    /// Tick under the timeline handle while it is dragged.
+    scrub_tick: Option<u64>,
+    replay_status: String,
 }
 
 #[derive(Default)]
@@ -27,17 +47,11 @@ struct OccupiedScreenSpace {
     bottom: f32,
 }
 
-#[derive(Default)]
-pub struct WorldMetrics {
-    pub seeds_count: RingBuffer<f64>,
-    pub cells_count: RingBuffer<f64>,
-}
-
 pub struct InfoBoardPlugin;
 
 impl Plugin for InfoBoardPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins((FrameTimeDiagnosticsPlugin, EguiPlugin))
+        app.add_plugins((FrameTimeDiagnosticsPlugin, EguiPlugin, LogConsolePlugin, CommandConsolePlugin))
             .init_resource::<InfoBoardSettings>()
             .add_systems(PostStartup, info_board_setup_system)
             .add_systems(Update, (change_text_system, cursor_events, ui_example_system));
@@ -45,18 +59,30 @@ impl Plugin for InfoBoardPlugin {
 }
 fn info_board_setup_system(mut info_board_settings: ResMut<InfoBoardSettings>) {
     setup_buffer(&mut info_board_settings.frames, 50);
-    setup_buffer(&mut info_board_settings.world_metrics.seeds_count, 50);
-    setup_buffer(&mut info_board_settings.world_metrics.cells_count, 50);
 }
 
-fn setup_buffer(input_buffer: &mut RingBuffer<f64>, capacity: usize) {
+fn setup_buffer(input_buffer: &mut RollingStats, capacity: usize) {
     input_buffer.increase_capacity(capacity);
     input_buffer.push(0.);
 }
 
+#[allow(clippy::too_many_arguments)]
 fn ui_example_system(
     mut contexts: EguiContexts,
     mut info_board_settings: ResMut<InfoBoardSettings>,
+    world_metrics: Res<WorldMetrics>,
+    species_registry: Res<SpeciesRegistry>,
+    clock: Res<WorldClock>,
+    mut replay: ResMut<ReplayState>,
+    tick: Res<SimulationTick>,
+    lineage: Res<LineageRecords>,
+    cells: Query<(&Genome, &Cell, &CellId, &SpeciesId, Has<Predator>)>,
+    mut log_console: ResMut<LogConsole>,
+    mut command_console: ResMut<CommandConsole>,
+    mut command_lines: EventWriter<CommandLine>,
+    config: Res<SimulationConfig>,
+    paused: Res<SimulationPaused>,
+    mut inputs: EventWriter<UserInput>,
 ) {
     let ctx = contexts.ctx_mut();
 
@@ -68,16 +94,43 @@ fn ui_example_system(
             ui.add_space(24.);
 
             //FPS
-            let last_fps = info_board_settings.frames.get_last().unwrap();
+            let frames = &info_board_settings.frames;
+            let last_fps = frames.get_last().unwrap();
             ui.heading(format!("FPS: {last_fps:.1}"));
-            example_plot(ui, &info_board_settings.frames, "Frames plot");
+            ui.label(format!(
+                "Mean {:.1} ± {:.1}, low {:.1}, 5th percentile {:.1}",
+                frames.mean().unwrap_or_default(),
+                frames.variance().unwrap_or_default().sqrt(),
+                frames.min().unwrap_or_default(),
+                frames.percentile(0.05).unwrap_or_default(),
+            ));
+            frames_chart(ui, &info_board_settings.frames, "Frames plot");
+            ui.separator();
+            ui.add_space(24.);
+
+            //CLOCK
+            let (hours, minutes) = clock.hours_minutes();
+            ui.heading(format!(
+                "Day {}, {hours:02}:{minutes:02}, {}",
+                clock.day + 1,
+                clock.season.as_str()
+            ));
+            ui.add(egui::ProgressBar::new(clock.light()).text(format!("Light: {:.0}%", clock.light() * 100.)));
+            ui.separator();
+            ui.add_space(24.);
+
+            //REPLAY
+            if paused.0 {
+                ui.heading("PAUSED");
+            }
+            replay_timeline(ui, &mut info_board_settings, &mut replay, tick.0);
             ui.separator();
             ui.add_space(24.);
 
             //POSITION
             let window_pos = info_board_settings.window_cursor_position;
             let world_pos = info_board_settings.world_cursor_position;
-            ui.heading(format!("Mouse positions:"));
+            ui.heading("Mouse positions:");
             ui.label(format!(
                 "Window: x: {:.2}, y: {:.2}",
                 window_pos.x, window_pos.y
@@ -90,52 +143,128 @@ fn ui_example_system(
             ui.add_space(24.);
 
             //SEEDS
-            let seeds_count = info_board_settings
-                .world_metrics
-                .seeds_count
-                .get_last()
-                .unwrap();
+            let seeds_count = world_metrics.seeds_count.last().unwrap_or_default();
             ui.heading(format!("SEEDS: {seeds_count:}"));
-            example_plot(
-                ui,
-                &info_board_settings.world_metrics.seeds_count,
-                "Seeds plot",
-            );
-            ui.separator();
-            ui.add_space(24.);
+
+            //PLANTS
+            let plants_count = world_metrics.plants_count.last().unwrap_or_default();
+            ui.heading(format!("PLANTS: {plants_count:}"));
 
             //CELLS
-            let cells_count = info_board_settings
-                .world_metrics
-                .cells_count
-                .get_last()
-                .unwrap();
+            let cells_count = world_metrics.cells_count.last().unwrap_or_default();
             ui.heading(format!("CELLS: {cells_count:}"));
-            example_plot(
-                ui,
-                &info_board_settings.world_metrics.cells_count,
-                "Cells plot",
-            );
+
+            //PREDATORS
+            let predators_count = world_metrics.predators_count.last().unwrap_or_default();
+            ui.heading(format!("PREDATORS: {predators_count:}"));
+            ui.add_space(12.);
+
+            //CHARTS
+            chart_controls(ui, &mut info_board_settings.charts, world_metrics.boards.len());
+            if !info_board_settings.charts.popped_out {
+                metrics_charts(ui, &info_board_settings.charts, &world_metrics, &species_registry, 120.);
+            }
+            ui.separator();
+            ui.add_space(24.);
+
+            //ISLANDS
+            if world_metrics.boards.len() > 1 {
+                ui.heading(format!("ISLANDS: {}", world_metrics.boards.len()));
+                for board in &world_metrics.boards {
+                    ui.label(format!(
+                        "{}: {} cells, {} predators, {} seeds",
+                        board.name,
+                        board.cells_count.last().unwrap_or_default(),
+                        board.predators_count.last().unwrap_or_default(),
+                        board.seeds_count.last().unwrap_or_default(),
+                    ));
+                }
+                ui.separator();
+                ui.add_space(24.);
+            }
+
+            //SPECIES
+            let living_species = species_registry.living().count();
+            ui.heading(format!("SPECIES: {living_species}"));
+            for species in species_registry.living().take(8) {
+                ui.horizontal(|ui| {
+                    let (rect, _) = ui.allocate_exact_size(egui::vec2(10., 10.), egui::Sense::hover());
+                    ui.painter().rect_filled(rect, 2., egui_color(species.color));
+                    ui.label(format!("#{}: {}", species.id.0, species.population));
+                });
+            }
+            ui.separator();
+            ui.add_space(24.);
+
+            //COMPOSITION
+            egui::CollapsingHeader::new(egui::RichText::new("COMPOSITION").heading())
+                .default_open(true)
+                .show(ui, |ui| {
+                    let samples: Vec<CellSample> = cells
+                        .iter()
+                        .map(|(genome, cell, id, species, predator)| CellSample {
+                            genome: *genome,
+                            energy: cell.energy,
+                            age: lineage.get(*id).map_or(0, |record| tick.0.saturating_sub(record.birth_tick)),
+                            species: *species,
+                            predator,
+                        })
+                        .collect();
+                    composition_charts(ui, &mut info_board_settings.composition, &samples, &species_registry);
+                });
+            ui.separator();
+            ui.add_space(24.);
+
+            //PARAMETERS
+            let header = egui::RichText::new("PARAMETERS").heading();
+            let parameters = egui::CollapsingHeader::new(header).show(ui, |ui| {
+                // Resets go back to the config the run started from.
+                let defaults = &replay.replay.config;
+                parameters_panel_ui(ui, &mut info_board_settings.parameters, &config, defaults, &mut inputs)
+            });
+            if parameters.body_returned == Some(true) {
+                replay.restart();
+            }
             ui.separator();
             ui.add_space(24.);
 
             //ADVANCED
-            ui.heading(format!("Advanced:"));
-            ui.checkbox(&mut info_board_settings.show_logs, "Show logs");
+            ui.heading("Advanced:");
+            ui.checkbox(&mut info_board_settings.show_logs, "Show log and command console");
             ui.separator();
         })
         .response
         .rect
         .width();
 
+    let charts = &mut info_board_settings.charts;
+    if charts.popped_out {
+        let mut open = true;
+        egui::Window::new("Metrics")
+            .open(&mut open)
+            .resizable(true)
+            .default_size([560., 420.])
+            .show(ctx, |ui| {
+                chart_controls(ui, charts, world_metrics.boards.len());
+                // Split what is left between the two charts.
+                let height = (ui.available_height() / 2. - 8.).max(80.);
+                metrics_charts(ui, charts, &world_metrics, &species_registry, height);
+            });
+        charts.popped_out = open;
+    }
+
     if info_board_settings.show_logs {
+        let parameters = config.parameter_names();
+        let species: Vec<u32> = species_registry.living().map(|species| species.id.0).collect();
         info_board_settings.occupied_screen_space.bottom =
             egui::TopBottomPanel::bottom("bottom_panel")
                 .resizable(true)
                 .min_height(230.)
                 .show(ctx, |ui| {
-                    ui.label("Bottom resizeable panel");
-                    ui.allocate_rect(ui.available_rect_before_wrap(), egui::Sense::hover());
+                    egui::TopBottomPanel::bottom("command_prompt").show_inside(ui, |ui| {
+                        command_prompt_ui(ui, &mut command_console, &parameters, &species, &mut command_lines)
+                    });
+                    log_console_ui(ui, &mut log_console);
                 })
                 .response
                 .rect
@@ -143,31 +272,53 @@ fn ui_example_system(
     }
 }
 
-fn example_plot(ui: &mut egui::Ui, frames: &RingBuffer<f64>, id: &str) -> egui::Response {
-    let n = 128;
-    let mut position = 0;
-    let line_points: PlotPoints = frames
-        .iter()
-        .map(|i| {
-            position = position + 1;
-            [position as f64, *i]
-        })
-        .collect();
-    let line = Line::new(line_points).color(Rgba::from_rgb(55., 55., 155.));
-
-    Plot::new(id)
-        .height(64.0)
-        .show_axes([false, false])
-        .allow_scroll(false)
-        .allow_drag(false)
-        .allow_zoom(false)
-        .data_aspect(1.0)
-        .show(ui, |plot_ui| plot_ui.line(line))
-        .response
+/// Replay mode, a scrubber over the recorded ticks, rewind buttons and replay file buttons.
+fn replay_timeline(ui: &mut egui::Ui, settings: &mut InfoBoardSettings, replay: &mut ReplayState, tick: u64) {
+    let mode = match (replay.seek, replay.mode) {
+        (Some(target), _) => format!("seeking to {target}"),
+        (None, ReplayMode::Recording) => "recording".to_string(),
+        (None, ReplayMode::Playing) => "playing".to_string(),
+    };
+    ui.heading(format!("REPLAY: tick {tick}, {mode}"));
+
+    let length = replay.length(tick).max(1);
+    let mut target = settings.scrub_tick.or(replay.seek).unwrap_or(tick);
+    let response = ui.add(egui::Slider::new(&mut target, 0..=length).text("Tick"));
+    // Seeking back replays the run from its start, so only jump once the handle is let go.
+    if response.dragged() {
+        settings.scrub_tick = Some(target);
+    } else if response.drag_released() || response.changed() {
+        settings.scrub_tick = None;
+        replay.seek(target, tick);
+    }
+
+    ui.horizontal(|ui| {
+        if ui.add_enabled(tick > 0, egui::Button::new("⏮ Rewind")).clicked() {
+            replay.seek(tick.saturating_sub(REWIND_TICKS), tick);
+        }
+        if ui.add_enabled(tick < length, egui::Button::new("Fast-forward ⏭")).clicked() {
+            replay.seek(tick + REWIND_TICKS, tick);
+        }
+    });
+
+    ui.horizontal(|ui| {
+        if ui.button(format!("Save {REPLAY_PATH}")).clicked() {
+            settings.replay_status = match replay.recording(tick).save(Path::new(REPLAY_PATH)) {
+                Ok(()) => format!("Saved {REPLAY_PATH}"),
+                Err(err) => format!("Failed to save {REPLAY_PATH}: {err}"),
+            };
+        }
+        // The recorded inputs after this tick belong to the run being left.
+        if replay.mode == ReplayMode::Playing && ui.button("Branch here").clicked() {
+            replay.take_over(tick);
+        }
+    });
+    if !settings.replay_status.is_empty() {
+        ui.label(&settings.replay_status);
+    }
 }
 
 fn change_text_system(
-    time: Res<Time>,
     diagnostics: Res<DiagnosticsStore>,
     mut info_board_settings: ResMut<InfoBoardSettings>,
 ) {
@@ -178,7 +329,7 @@ fn change_text_system(
         }
     }
 
-    info_board_settings.frames.push(fps)
+    info_board_settings.frames.push(fps);
 }
 
 fn cursor_events(
@@ -0,0 +1,132 @@
+use std::fs;
+
+use bevy::{math::Vec3Swizzles, prelude::*};
+use bevy_egui::{egui, EguiContexts};
+
+use crate::{
+    core::{lineage::LineageRecords, simulation::SimulationTick},
+    entities::{cells::*, genome::Genome},
+};
+
+const NEWICK_PATH: &str = "lineage.nwk";
+const DOT_PATH: &str = "lineage.dot";
+
+pub struct LineageViewPlugin;
+
+impl Plugin for LineageViewPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LineageViewSettings>()
+            .add_systems(Update, (lineage_window_system, highlight_descendants_system));
+    }
+}
+
+#[derive(Default, Resource)]
+struct LineageViewSettings {
+    ancestor: Option<CellId>,
+    status: String,
+}
+
+fn lineage_window_system(
+    mut contexts: EguiContexts,
+    mut settings: ResMut<LineageViewSettings>,
+    lineage: Res<LineageRecords>,
+    tick: Res<SimulationTick>,
+) {
+    let ctx = contexts.ctx_mut();
+
+    egui::Window::new("Lineage")
+        .default_open(false)
+        .resizable(true)
+        .show(ctx, |ui| {
+            ui.label(format!("Cells recorded: {}", lineage.len()));
+
+            ui.horizontal(|ui| {
+                ui.label("Ancestor:");
+                let mut id = settings.ancestor.map_or(0, |id| id.0);
+                if ui.add(egui::DragValue::new(&mut id).prefix("#")).changed() {
+                    settings.ancestor = Some(CellId(id));
+                }
+                if ui.button("Clear").clicked() {
+                    settings.ancestor = None;
+                }
+            });
+
+            if let Some(ancestor) = settings.ancestor {
+                match lineage.get(ancestor) {
+                    Some(record) => {
+                        let descendants = lineage.descendants(ancestor);
+                        let living = descendants
+                            .iter()
+                            .filter(|id| lineage.get(**id).is_some_and(|r| r.is_alive()))
+                            .count();
+                        let parent = record
+                            .parent
+                            .map_or("founder".to_string(), |parent| format!("#{}", parent.0));
+                        ui.label(format!("Parent: {parent}, born at tick {}", record.birth_tick));
+                        match (record.death_tick, record.death_cause) {
+                            (Some(death_tick), Some(cause)) => {
+                                ui.label(format!("Died at tick {death_tick} ({})", cause.as_str()));
+                            }
+                            _ => {
+                                ui.label("Alive");
+                            }
+                        }
+                        ui.label(format!("Descendants: {} ({living} living)", descendants.len()));
+                    }
+                    None => {
+                        ui.label("No such cell");
+                    }
+                }
+            }
+
+            ui.separator();
+            ui.label("Founders:");
+            egui::ScrollArea::vertical().max_height(120.).show(ui, |ui| {
+                for root in lineage.roots() {
+                    let selected = settings.ancestor == Some(root);
+                    if ui.selectable_label(selected, format!("#{}", root.0)).clicked() {
+                        settings.ancestor = Some(root);
+                    }
+                }
+            });
+
+            ui.separator();
+            ui.horizontal(|ui| {
+                if ui.button("Export Newick").clicked() {
+                    settings.status = export(NEWICK_PATH, lineage.to_newick(tick.0));
+                }
+                if ui.button("Export DOT").clicked() {
+                    settings.status = export(DOT_PATH, lineage.to_dot());
+                }
+            });
+            if !settings.status.is_empty() {
+                ui.label(&settings.status);
+            }
+        });
+}
+
+fn export(path: &str, contents: String) -> String {
+    match fs::write(path, contents) {
+        Ok(()) => format!("Saved {path}"),
+        Err(err) => format!("Failed to save {path}: {err}"),
+    }
+}
+
+fn highlight_descendants_system(
+    mut gizmos: Gizmos,
+    settings: Res<LineageViewSettings>,
+    lineage: Res<LineageRecords>,
+    cells: Query<(&Transform, &CellId, &Genome), With<Cell>>,
+) {
+    let Some(ancestor) = settings.ancestor else { return };
+    let mut family = lineage.descendants(ancestor);
+    family.push(ancestor);
+    family.sort_unstable();
+
+    for (transform, id, genome) in cells.iter() {
+        if family.binary_search(id).is_ok() {
+            let color = if *id == ancestor { Color::WHITE } else { Color::GOLD };
+            gizmos.circle_2d(transform.translation.xy(), genome.radius + 3., color);
+        }
+    }
+}
@@ -0,0 +1,302 @@
+use std::{
+    collections::{BTreeSet, VecDeque},
+    fmt::{Debug, Write},
+    sync::Mutex,
+};
+
+use bevy::{
+    log::{
+        tracing_subscriber::{
+            layer::{Context, SubscriberExt},
+            Layer,
+        },
+        BoxedSubscriber, Level,
+    },
+    prelude::*,
+    utils::tracing::{
+        field::{Field, Visit},
+        Event as TracingEvent, Subscriber,
+    },
+};
+use bevy_egui::egui;
+
+use crate::{
+    actions::cells_plugin::*,
+    core::{config::SimulationConfig, lineage::CellDiedEvent, replay::*, simulation::SimulationTick},
+    utils::ring_buffer::RingBuffer,
+};
+
+/// Lines the console keeps, the oldest are dropped first.
+const LOG_CAPACITY: usize = 5_000;
+
+/// Lines logged through `tracing` that the console has not collected yet.
+static CAPTURED: Mutex<VecDeque<LogLine>> = Mutex::new(VecDeque::new());
+
+/// Collects `tracing` output and simulation events into the [`LogConsole`].
+pub struct LogConsolePlugin;
+
+impl Plugin for LogConsolePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LogConsole>()
+            .add_systems(PostUpdate, (simulation_log_system, collect_logs_system).chain());
+    }
+}
+
+/// For `LogPlugin::update_subscriber`: also sends every log event to the console.
+pub fn capture_logs(subscriber: BoxedSubscriber) -> BoxedSubscriber {
+    Box::new(subscriber.with(CaptureLayer))
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LogKind {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+    Birth,
+    Death,
+    Eat,
+    /// Config changes and user inputs.
+    World,
+    /// Console commands and their replies.
+    Command,
+}
+
+impl LogKind {
+    const ALL: [LogKind; 10] = [
+        Self::Error,
+        Self::Warn,
+        Self::Info,
+        Self::Debug,
+        Self::Trace,
+        Self::Birth,
+        Self::Death,
+        Self::Eat,
+        Self::World,
+        Self::Command,
+    ];
+
+    fn from_level(level: Level) -> Self {
+        match level {
+            Level::ERROR => Self::Error,
+            Level::WARN => Self::Warn,
+            Level::INFO => Self::Info,
+            Level::DEBUG => Self::Debug,
+            _ => Self::Trace,
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Error => "Error",
+            Self::Warn => "Warn",
+            Self::Info => "Info",
+            Self::Debug => "Debug",
+            Self::Trace => "Trace",
+            Self::Birth => "Births",
+            Self::Death => "Deaths",
+            Self::Eat => "Eats",
+            Self::World => "World",
+            Self::Command => "Command",
+        }
+    }
+
+    fn color(self) -> egui::Color32 {
+        match self {
+            Self::Error => egui::Color32::from_rgb(230, 80, 80),
+            Self::Warn => egui::Color32::from_rgb(230, 180, 60),
+            Self::Info => egui::Color32::LIGHT_GRAY,
+            Self::Debug | Self::Trace => egui::Color32::GRAY,
+            Self::Birth => egui::Color32::from_rgb(110, 200, 120),
+            Self::Death => egui::Color32::from_rgb(200, 120, 120),
+            Self::Eat => egui::Color32::from_rgb(200, 170, 110),
+            Self::World => egui::Color32::from_rgb(120, 160, 230),
+            Self::Command => egui::Color32::WHITE,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct LogLine {
+    pub kind: LogKind,
+    /// Module the line was logged from, empty for simulation events.
+    pub target: String,
+    pub message: String,
+    /// `SimulationTick` the line was collected at.
+    pub tick: u64,
+}
+
+/// What was logged, and how the console panel shows it.
+#[derive(Resource)]
+pub struct LogConsole {
+    lines: RingBuffer<LogLine>,
+    hidden: BTreeSet<LogKind>,
+    search: String,
+    /// Whether the view stays at the newest line.
+    follow: bool,
+}
+
+impl Default for LogConsole {
+    fn default() -> Self {
+        Self {
+            lines: RingBuffer::new(LOG_CAPACITY),
+            hidden: BTreeSet::new(),
+            search: String::new(),
+            follow: true,
+        }
+    }
+}
+
+impl LogConsole {
+    pub fn lines(&self) -> impl DoubleEndedIterator<Item = &LogLine> {
+        self.lines.iter()
+    }
+
+    pub fn push(&mut self, kind: LogKind, tick: u64, message: String) {
+        self.lines.push(LogLine {
+            kind,
+            target: String::new(),
+            message,
+            tick,
+        });
+    }
+
+    /// Whether `line` passes the kind filters and contains `query`, which is
+    /// the search already lowercased.
+    fn shows(&self, line: &LogLine, query: &str) -> bool {
+        !self.hidden.contains(&line.kind)
+            && (query.is_empty()
+                || line.target.to_lowercase().contains(query)
+                || line.message.to_lowercase().contains(query))
+    }
+}
+
+struct CaptureLayer;
+
+impl<S: Subscriber> Layer<S> for CaptureLayer {
+    fn on_event(&self, event: &TracingEvent<'_>, _: Context<'_, S>) {
+        let mut message = MessageVisitor(String::new());
+        event.record(&mut message);
+        let metadata = event.metadata();
+        let Ok(mut captured) = CAPTURED.lock() else { return };
+        // Nothing collects without the console plugin, so keep no more than it would.
+        if captured.len() >= LOG_CAPACITY {
+            captured.pop_front();
+        }
+        captured.push_back(LogLine {
+            kind: LogKind::from_level(*metadata.level()),
+            target: metadata.target().to_string(),
+            message: message.0,
+            tick: 0,
+        });
+    }
+}
+
+/// The `message` of an event followed by its other fields as `name=value`.
+struct MessageVisitor(String);
+
+impl Visit for MessageVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.0, "{value:?}");
+        } else {
+            let _ = write!(self.0, " {}={value:?}", field.name());
+        }
+    }
+}
+
+fn collect_logs_system(mut console: ResMut<LogConsole>, tick: Res<SimulationTick>) {
+    let Ok(mut captured) = CAPTURED.lock() else { return };
+    for mut line in captured.drain(..) {
+        line.tick = tick.0;
+        console.lines.push(line);
+    }
+}
+
+/// Births, deaths, meals, user inputs and config changes of this tick.
+#[allow(clippy::too_many_arguments)]
+fn simulation_log_system(
+    mut console: ResMut<LogConsole>,
+    tick: Res<SimulationTick>,
+    config: Res<SimulationConfig>,
+    replay: Res<ReplayState>,
+    mut spawned: EventReader<SpawnCellEvent>,
+    mut died: EventReader<CellDiedEvent>,
+    mut ate: EventReader<CellAteEvent>,
+    mut inputs: EventReader<UserInput>,
+) {
+    // Ticks replayed while seeking already happened once.
+    if replay.seek.is_some() {
+        spawned.clear();
+        died.clear();
+        ate.clear();
+        inputs.clear();
+        return;
+    }
+    let tick = tick.0;
+
+    for spawn in spawned.read() {
+        let kind = if spawn.predator { "predator" } else { "cell" };
+        let message = match spawn.parent {
+            Some(parent) => format!("Cell #{} divided into a new {kind}", parent.0),
+            None => format!("New {kind} at ({:.0}, {:.0})", spawn.position.x, spawn.position.y),
+        };
+        console.push(LogKind::Birth, tick, message);
+    }
+    for death in died.read() {
+        console.push(LogKind::Death, tick, format!("Cell #{} died: {}", death.id.0, death.cause.as_str()));
+    }
+    for meal in ate.read() {
+        let food = match meal.food {
+            Food::Seed => "a seed".to_string(),
+            Food::Prey(prey) => format!("cell #{}", prey.0),
+            Food::Corpse => "a corpse".to_string(),
+        };
+        console.push(LogKind::Eat, tick, format!("Cell #{} ate {food}", meal.id.0));
+    }
+    for input in inputs.read() {
+        console.push(LogKind::World, tick, format!("User input: {input:?}"));
+    }
+    if config.is_changed() && !config.is_added() {
+        console.push(LogKind::World, tick, "Config changed".to_string());
+    }
+}
+
+/// Kind filters, search and follow toggle above the lines that pass them.
+pub(crate) fn log_console_ui(ui: &mut egui::Ui, console: &mut LogConsole) {
+    ui.horizontal_wrapped(|ui| {
+        for kind in LogKind::ALL {
+            let mut shown = !console.hidden.contains(&kind);
+            let label = egui::RichText::new(kind.as_str()).color(kind.color());
+            if ui.checkbox(&mut shown, label).changed() {
+                if shown {
+                    console.hidden.remove(&kind);
+                } else {
+                    console.hidden.insert(kind);
+                }
+            }
+        }
+        ui.separator();
+        ui.add(egui::TextEdit::singleline(&mut console.search).hint_text("Search").desired_width(140.));
+        ui.checkbox(&mut console.follow, "Follow");
+        if ui.button("Clear").clicked() {
+            console.lines.clear();
+        }
+    });
+    ui.separator();
+
+    let query = console.search.to_lowercase();
+    let shown: Vec<&LogLine> = console.lines.iter().filter(|line| console.shows(line, &query)).collect();
+    let row_height = ui.text_style_height(&egui::TextStyle::Monospace);
+    egui::ScrollArea::vertical()
+        .auto_shrink([false, false])
+        .stick_to_bottom(console.follow)
+        .show_rows(ui, row_height, shown.len(), |ui, rows| {
+            for line in &shown[rows] {
+                let source = if line.target.is_empty() { String::new() } else { format!(" {}", line.target) };
+                let text = format!("{:>7} {:<6}{source}: {}", line.tick, line.kind.as_str(), line.message);
+                ui.label(egui::RichText::new(text).monospace().color(line.kind.color()));
+            }
+        });
+}
@@ -1,2 +1,9 @@
-pub(crate) mod debug;
-pub(crate) mod info_board;
\ No newline at end of file
+pub mod charts;
+pub mod command_console;
+pub mod debug;
+pub mod field_overlay;
+pub mod info_board;
+pub mod lineage_view;
+pub mod log_console;
+pub mod parameters_panel;
+pub mod world_editor;
\ No newline at end of file
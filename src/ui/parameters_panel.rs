@@ -0,0 +1,170 @@
+use std::collections::BTreeMap;
+
+use bevy::prelude::*;
+use bevy_egui::egui;
+
+use crate::core::{config::SimulationConfig, replay::UserInput};
+
+/// Sections of the panel, by the part of the parameter name before the first dot.
+const SECTIONS: [(&str, &str); 9] = [
+    ("board", "Board"),
+    ("seeds", "Seeds"),
+    ("plants", "Plants"),
+    ("cells", "Cells"),
+    ("predators", "Predators"),
+    ("species", "Species"),
+    ("corpses", "Corpses"),
+    ("clock", "Clock"),
+    ("fields", "Fields"),
+];
+
+/// Parameters only read when the world is (re)started.
+const AT_START: [&str; 8] = [
+    "board.width",
+    "board.height",
+    "seeds.step",
+    "seeds.grid",
+    "cells.initial",
+    "predators.initial",
+    "plants.initial",
+    "fields.cell_size",
+];
+
+/// Shown with the parameters of [`AT_START`], which are marked with a `*`.
+const AT_START_NOTE: &str = "* Only used when the world is started: the running world keeps its value until it is restarted with these parameters";
+
+const WHOLE_NUMBERS: [&str; 6] = [
+    "cells.initial",
+    "predators.initial",
+    "plants.initial",
+    "plants.seed_interval",
+    "clock.day_length",
+    "clock.days_per_season",
+];
+
+/// What is being edited in the parameters panel.
+#[derive(Default)]
+pub(crate) struct ParameterPanel {
+    /// Values of the parameters whose handle is being dragged, sent once it is let go.
+    drafts: BTreeMap<String, f64>,
+    /// Why the last edit was turned down.
+    error: Option<String>,
+}
+
+/// Collapsible sections with a field per parameter, sending changes as
+/// [`UserInput::SetParameter`] so they are recorded. `defaults` is what the
+/// reset buttons go back to. Returns whether the world should restart with
+/// the [`AT_START`] parameters changed since `defaults`.
+pub(crate) fn parameters_panel_ui(
+    ui: &mut egui::Ui,
+    panel: &mut ParameterPanel,
+    config: &SimulationConfig,
+    defaults: &SimulationConfig,
+    inputs: &mut EventWriter<UserInput>,
+) -> bool {
+    let names = config.parameter_names();
+    for (prefix, title) in SECTIONS {
+        let section: Vec<&String> = names
+            .iter()
+            .filter(|name| name.split('.').next() == Some(prefix))
+            .collect();
+        let changed: Vec<(&String, f64)> = section
+            .iter()
+            .filter_map(|name| Some((*name, defaults.parameter(name)?)))
+            .filter(|(name, default)| config.parameter(name) != Some(*default))
+            .collect();
+
+        egui::CollapsingHeader::new(title).id_source(("parameters", prefix)).show(ui, |ui| {
+            egui::Grid::new(("parameters grid", prefix)).num_columns(3).striped(true).show(ui, |ui| {
+                for name in &section {
+                    parameter_row(ui, panel, name, config, defaults, inputs);
+                    ui.end_row();
+                }
+            });
+            if section.iter().any(|name| AT_START.contains(&name.as_str())) {
+                ui.small(AT_START_NOTE);
+            }
+            if ui
+                .add_enabled(!changed.is_empty(), egui::Button::new(format!("Reset {}", title.to_lowercase())))
+                .clicked()
+            {
+                for (name, value) in &changed {
+                    set(panel, config, name, *value, inputs);
+                }
+            }
+        });
+    }
+    if let Some(error) = &panel.error {
+        ui.colored_label(egui::Color32::from_rgb(230, 80, 80), error);
+    }
+
+    let pending = AT_START.iter().any(|name| config.parameter(name) != defaults.parameter(name));
+    let restart = egui::Button::new("Restart with these parameters");
+    ui.add_enabled(pending, restart)
+        .on_hover_text("Starts a new run from the parameters above; the recording so far is dropped")
+        .clicked()
+}
+
+fn parameter_row(
+    ui: &mut egui::Ui,
+    panel: &mut ParameterPanel,
+    name: &str,
+    config: &SimulationConfig,
+    defaults: &SimulationConfig,
+    inputs: &mut EventWriter<UserInput>,
+) {
+    let Some(current) = config.parameter(name) else { return };
+    let (_, setting) = name.split_once('.').unwrap_or(("", name));
+    let label = setting.replace(['_', '.'], " ");
+    if AT_START.contains(&name) {
+        ui.label(format!("{label} *")).on_hover_text(AT_START_NOTE);
+    } else {
+        ui.label(label);
+    }
+
+    if name == "seeds.grid" {
+        let mut on = current != 0.;
+        if ui.checkbox(&mut on, "").changed() {
+            set(panel, config, name, on as u8 as f64, inputs);
+        }
+    } else {
+        let mut value = panel.drafts.get(name).copied().unwrap_or(current);
+        let speed = (current.abs() * 0.01).max(0.001);
+        let mut field = egui::DragValue::new(&mut value).speed(speed);
+        field = if WHOLE_NUMBERS.contains(&name) { field.fixed_decimals(0) } else { field.max_decimals(4) };
+        let response = ui.add(field);
+        if response.changed() {
+            panel.drafts.insert(name.to_string(), value);
+        }
+        // Typed values arrive without a drag, dragged ones once the handle is let go.
+        if (response.changed() && !response.dragged()) || response.drag_released() {
+            if let Some(value) = panel.drafts.remove(name) {
+                set(panel, config, name, value, inputs);
+            }
+        }
+    }
+
+    let default = defaults.parameter(name);
+    let reset = egui::Button::new("↺").small();
+    let clicked = ui
+        .add_enabled(default.is_some_and(|default| default != current), reset)
+        .on_hover_text(format!("Reset to {}", default.unwrap_or(current)))
+        .clicked();
+    if let Some(default) = default.filter(|_| clicked) {
+        set(panel, config, name, default, inputs);
+    }
+}
+
+/// Sends the change unless `set_parameter` turns it down.
+fn set(panel: &mut ParameterPanel, config: &SimulationConfig, name: &str, value: f64, inputs: &mut EventWriter<UserInput>) {
+    match config.clone().set_parameter(name, value) {
+        Ok(()) => {
+            panel.error = None;
+            inputs.send(UserInput::SetParameter {
+                name: name.to_string(),
+                value,
+            });
+        }
+        Err(err) => panel.error = Some(err),
+    }
+}
@@ -0,0 +1,114 @@
+use std::{fs, path::Path};
+
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+
+use crate::{
+    actions::input_plugin::{BoardTool, WorldEditor, MOVE_COST_RANGE},
+    core::{config::SimulationConfig, replay::UserInput},
+    entities::terrain::WorldLayout,
+};
+
+const WORLD_PATH: &str = "world.ron";
+
+pub struct WorldEditorPlugin;
+
+impl Plugin for WorldEditorPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<WorldEditorStatus>()
+            .add_systems(Update, world_editor_window_system);
+    }
+}
+
+#[derive(Default, Resource)]
+struct WorldEditorStatus(String);
+
+fn world_editor_window_system(
+    mut contexts: EguiContexts,
+    mut editor: ResMut<WorldEditor>,
+    config: Res<SimulationConfig>,
+    mut inputs: EventWriter<UserInput>,
+    mut status: ResMut<WorldEditorStatus>,
+) {
+    let ctx = contexts.ctx_mut();
+
+    egui::Window::new("World editor")
+        .default_open(false)
+        .show(ctx, |ui| {
+            let previous = editor.tool;
+            ui.horizontal_wrapped(|ui| {
+                ui.radio_value(&mut editor.tool, BoardTool::SpawnCells, "Cells");
+                ui.radio_value(&mut editor.tool, BoardTool::Rectangle, "Rectangle");
+                ui.radio_value(&mut editor.tool, BoardTool::Circle, "Circle");
+                ui.radio_value(&mut editor.tool, BoardTool::Polyline, "Wall");
+                ui.radio_value(&mut editor.tool, BoardTool::Terrain, "Terrain");
+                ui.radio_value(&mut editor.tool, BoardTool::Erase, "Erase");
+            });
+            if editor.tool != previous {
+                editor.cancel();
+            }
+            match editor.tool {
+                BoardTool::Polyline => {
+                    ui.add(egui::Slider::new(&mut editor.wall_width, 1. ..=40.).text("Wall width"));
+                    ui.label("Left click adds a point, right click finishes the wall.");
+                }
+                BoardTool::Terrain => {
+                    ui.add(egui::Slider::new(&mut editor.move_cost, MOVE_COST_RANGE).text("Move cost"));
+                    ui.label("Drag to place a region.");
+                }
+                BoardTool::Rectangle | BoardTool::Circle => {
+                    ui.label("Drag to place an obstacle.");
+                }
+                BoardTool::Erase => {
+                    ui.label("Click an obstacle or region to remove it.");
+                }
+                BoardTool::SpawnCells => {
+                    ui.label("Click spawns a cell, Shift+click a predator.");
+                }
+            }
+            ui.separator();
+
+            ui.label(format!(
+                "Obstacles: {}, terrain regions: {}",
+                config.world.obstacles.len(),
+                config.world.terrain.len()
+            ));
+            ui.horizontal(|ui| {
+                if ui.button(format!("Save {WORLD_PATH}")).clicked() {
+                    status.0 = save_world(&config.world, Path::new(WORLD_PATH));
+                }
+                if ui.button(format!("Load {WORLD_PATH}")).clicked() {
+                    status.0 = match load_world() {
+                        Ok(world) => {
+                            inputs.send(UserInput::SetWorld(world));
+                            format!("Loaded {WORLD_PATH}")
+                        }
+                        Err(err) => format!("Failed to load {WORLD_PATH}: {err}"),
+                    };
+                }
+                if ui.button("Clear").clicked() {
+                    inputs.send(UserInput::SetWorld(WorldLayout::default()));
+                }
+            });
+            if !status.0.is_empty() {
+                ui.label(&status.0);
+            }
+        });
+}
+
+pub(crate) fn save_world(world: &WorldLayout, path: &Path) -> String {
+    let written = ron::ser::to_string_pretty(world, ron::ser::PrettyConfig::default())
+        .map_err(|err| err.to_string())
+        .and_then(|text| fs::write(path, text).map_err(|err| err.to_string()));
+    match written {
+        Ok(()) => format!("Saved {}", path.display()),
+        Err(err) => format!("Failed to save {}: {err}", path.display()),
+    }
+}
+
+fn load_world() -> Result<WorldLayout, String> {
+    let text = fs::read_to_string(WORLD_PATH).map_err(|err| err.to_string())?;
+    let world: WorldLayout = ron::from_str(&text).map_err(|err| err.to_string())?;
+    world.validate()?;
+    Ok(world)
+}
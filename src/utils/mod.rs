@@ -1 +1,3 @@
-pub(crate) mod ring_buffer;
\ No newline at end of file
+pub mod ring_buffer;
+pub mod spatial_grid;
+pub mod time_series;
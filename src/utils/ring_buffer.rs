@@ -1,10 +1,36 @@
-use std::collections::VecDeque;
+use std::{
+    collections::{vec_deque, VecDeque},
+    ops::{Deref, Index},
+};
 
+use serde::{Deserialize, Serialize};
+
+/// The last `capacity` items pushed, oldest first.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(from = "RingBufferData<T>")]
 pub struct RingBuffer<T> {
     buffer: VecDeque<T>,
     capacity: usize,
 }
 
+/// The serialized form, trimmed to its capacity when read back.
+#[derive(Deserialize)]
+struct RingBufferData<T> {
+    buffer: VecDeque<T>,
+    capacity: usize,
+}
+
+impl<T> From<RingBufferData<T>> for RingBuffer<T> {
+    fn from(data: RingBufferData<T>) -> Self {
+        let mut buffer = Self {
+            buffer: data.buffer,
+            capacity: data.capacity,
+        };
+        buffer.shrink_capacity(data.capacity);
+        buffer
+    }
+}
+
 impl<T> RingBuffer<T> {
     pub fn new(capacity: usize) -> Self {
         Self {
@@ -13,61 +39,283 @@ impl<T> RingBuffer<T> {
         }
     }
 
-    pub fn push(&mut self, item: T) {
-        if self.buffer.len() >= self.capacity {
-            self.buffer.pop_front();
+    /// Adds `item` as the newest one. Returns what no longer fits: the oldest
+    /// item or, at zero capacity, `item` itself.
+    pub fn push(&mut self, item: T) -> Option<T> {
+        if self.capacity == 0 {
+            return Some(item);
         }
+        let evicted = if self.buffer.len() >= self.capacity {
+            self.buffer.pop_front()
+        } else {
+            None
+        };
         self.buffer.push_back(item);
+        evicted
     }
 
     pub fn get_last(&self) -> Option<&T> {
         self.buffer.back()
     }
 
+    /// Item `index` counted from the oldest.
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.buffer.get(index)
+    }
+
+    pub fn len(&self) -> usize {
+        self.buffer.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.buffer.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn clear(&mut self) {
+        self.buffer.clear();
+    }
+
     pub fn increase_capacity(&mut self, new_capacity: usize) {
         if new_capacity > self.capacity {
-            // Create a new buffer with the new capacity
-            let mut new_buffer = VecDeque::with_capacity(new_capacity);
-            
-            // Move elements from the old buffer to the new buffer
-            while let Some(item) = self.buffer.pop_front() {
-                new_buffer.push_back(item);
-            }
-            
-            self.buffer = new_buffer;
+            self.buffer.reserve(new_capacity - self.buffer.len());
             self.capacity = new_capacity;
         }
     }
 
-    pub fn iter(&self) -> RingBufferIterator<T> {
-        RingBufferIterator {
-            buffer: &self.buffer,
-            index: 0,
-        }
+    /// Lowers the capacity to `new_capacity`, dropping the oldest items that
+    /// no longer fit. Returns them, oldest first.
+    pub fn shrink_capacity(&mut self, new_capacity: usize) -> Vec<T> {
+        let excess = self.buffer.len().saturating_sub(new_capacity);
+        let dropped = self.buffer.drain(..excess).collect();
+        self.capacity = self.capacity.min(new_capacity);
+        self.buffer.shrink_to(self.capacity);
+        dropped
+    }
+
+    pub fn iter(&self) -> RingBufferIterator<'_, T> {
+        self.buffer.iter()
+    }
+}
+
+/// Items from the oldest to the newest, from either end.
+pub type RingBufferIterator<'a, T> = vec_deque::Iter<'a, T>;
+
+impl<T> Default for RingBuffer<T> {
+    fn default() -> Self {
+        Self::new(10)
     }
 }
 
-pub struct RingBufferIterator<'a, T> {
-    buffer: &'a VecDeque<T>,
-    index: usize,
+impl<T> Index<usize> for RingBuffer<T> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        &self.buffer[index]
+    }
 }
 
-impl<'a, T> Iterator for RingBufferIterator<'a, T> {
+impl<'a, T> IntoIterator for &'a RingBuffer<T> {
     type Item = &'a T;
+    type IntoIter = RingBufferIterator<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T> IntoIterator for RingBuffer<T> {
+    type Item = T;
+    type IntoIter = vec_deque::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.buffer.into_iter()
+    }
+}
+
+/// A buffer just big enough for every item.
+impl<T> FromIterator<T> for RingBuffer<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let buffer: VecDeque<T> = iter.into_iter().collect();
+        Self {
+            capacity: buffer.len(),
+            buffer,
+        }
+    }
+}
+
+/// Pushes every item in turn, so only the last `capacity` ones stay.
+impl<T> Extend<T> for RingBuffer<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.push(item);
+        }
+    }
+}
+
+/// A ring buffer of samples that keeps their mean, variance, min and max up to
+/// date as samples come and go, in constant amortized time per sample.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(from = "RingBuffer<f64>", into = "RingBuffer<f64>")]
+pub struct RollingStats {
+    samples: RingBuffer<f64>,
+    mean: f64,
+    /// Sum of squared differences from the mean.
+    squares: f64,
+    /// Number of samples ever pushed, to tell which window entries are still held.
+    pushed: u64,
+    /// Candidates for the min, increasing, tagged with their push number.
+    minima: VecDeque<(u64, f64)>,
+    /// Candidates for the max, decreasing.
+    maxima: VecDeque<(u64, f64)>,
+}
+
+impl RollingStats {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            samples: RingBuffer::new(capacity),
+            mean: 0.,
+            squares: 0.,
+            pushed: 0,
+            minima: VecDeque::new(),
+            maxima: VecDeque::new(),
+        }
+    }
+
+    pub fn push(&mut self, value: f64) {
+        if self.samples.capacity() == 0 {
+            return;
+        }
+        if let Some(evicted) = self.samples.push(value) {
+            self.remove(evicted);
+        }
+        self.add(value);
+    }
 
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.index < self.buffer.len() {
-            let item = &self.buffer[self.index];
-            self.index += 1;
-            Some(item)
+    /// Welford's update for a new sample.
+    fn add(&mut self, value: f64) {
+        let count = self.samples.len() as f64;
+        let delta = value - self.mean;
+        self.mean += delta / count;
+        self.squares += delta * (value - self.mean);
+
+        while self.minima.back().is_some_and(|(_, kept)| *kept >= value) {
+            self.minima.pop_back();
+        }
+        self.minima.push_back((self.pushed, value));
+        while self.maxima.back().is_some_and(|(_, kept)| *kept <= value) {
+            self.maxima.pop_back();
+        }
+        self.maxima.push_back((self.pushed, value));
+        self.pushed += 1;
+    }
+
+    /// The reverse of `add` for the oldest sample, already out of the buffer.
+    fn remove(&mut self, value: f64) {
+        let count = self.samples.len() as f64 - 1.;
+        if count <= 0. {
+            self.mean = 0.;
+            self.squares = 0.;
         } else {
-            None
+            let delta = value - self.mean;
+            self.mean -= delta / count;
+            self.squares = (self.squares - delta * (value - self.mean)).max(0.);
         }
+
+        let oldest = self.pushed - count as u64;
+        while self.minima.front().is_some_and(|(index, _)| *index < oldest) {
+            self.minima.pop_front();
+        }
+        while self.maxima.front().is_some_and(|(index, _)| *index < oldest) {
+            self.maxima.pop_front();
+        }
+    }
+
+    pub fn mean(&self) -> Option<f64> {
+        (!self.samples.is_empty()).then_some(self.mean)
+    }
+
+    /// Population variance of the samples held.
+    pub fn variance(&self) -> Option<f64> {
+        (!self.samples.is_empty()).then(|| self.squares / self.samples.len() as f64)
+    }
+
+    pub fn min(&self) -> Option<f64> {
+        self.minima.front().map(|(_, value)| *value)
+    }
+
+    pub fn max(&self) -> Option<f64> {
+        self.maxima.front().map(|(_, value)| *value)
+    }
+
+    /// The sample `fraction` of the way from the smallest to the largest,
+    /// interpolated between neighbours. Sorts a copy of the samples.
+    pub fn percentile(&self, fraction: f64) -> Option<f64> {
+        let mut sorted: Vec<f64> = self.samples.iter().copied().collect();
+        sorted.sort_by(f64::total_cmp);
+        let position = fraction.clamp(0., 1.) * (sorted.len().checked_sub(1)? as f64);
+        let (below, above) = (sorted[position.floor() as usize], sorted[position.ceil() as usize]);
+        Some(below + (above - below) * position.fract())
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::new(self.samples.capacity());
+    }
+
+    pub fn increase_capacity(&mut self, new_capacity: usize) {
+        self.samples.increase_capacity(new_capacity);
+    }
+
+    /// Lowers the capacity, forgetting the oldest samples that no longer fit.
+    pub fn shrink_capacity(&mut self, new_capacity: usize) {
+        let mut shrunk = Self::new(new_capacity);
+        shrunk.extend(self.samples.iter().copied());
+        *self = shrunk;
     }
 }
 
-impl<T> Default for RingBuffer<T> {
+impl Default for RollingStats {
     fn default() -> Self {
-        Self::new(10)
+        Self::from(RingBuffer::default())
+    }
+}
+
+/// Read access to the samples themselves.
+impl Deref for RollingStats {
+    type Target = RingBuffer<f64>;
+
+    fn deref(&self) -> &RingBuffer<f64> {
+        &self.samples
+    }
+}
+
+impl From<RingBuffer<f64>> for RollingStats {
+    fn from(samples: RingBuffer<f64>) -> Self {
+        let mut stats = Self::new(samples.capacity());
+        stats.extend(samples);
+        stats
+    }
+}
+
+impl From<RollingStats> for RingBuffer<f64> {
+    fn from(stats: RollingStats) -> Self {
+        stats.samples
+    }
+}
+
+impl FromIterator<f64> for RollingStats {
+    fn from_iter<I: IntoIterator<Item = f64>>(iter: I) -> Self {
+        Self::from(iter.into_iter().collect::<RingBuffer<f64>>())
+    }
+}
+
+impl Extend<f64> for RollingStats {
+    fn extend<I: IntoIterator<Item = f64>>(&mut self, iter: I) {
+        for value in iter {
+            self.push(value);
+        }
     }
 }
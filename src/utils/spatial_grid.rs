@@ -0,0 +1,67 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+
+/// Points bucketed into square cells, for nearest-neighbour queries with a range.
+pub struct SpatialGrid {
+    cell_size: f32,
+    buckets: HashMap<(i32, i32), Vec<Vec2>>,
+}
+
+impl SpatialGrid {
+    pub fn new(cell_size: f32, points: &[Vec2]) -> Self {
+        let mut grid = Self {
+            cell_size: cell_size.max(1.),
+            buckets: HashMap::new(),
+        };
+        for &point in points {
+            grid.buckets.entry(grid.cell_of(point)).or_default().push(point);
+        }
+        grid
+    }
+
+    fn cell_of(&self, point: Vec2) -> (i32, i32) {
+        let cell = (point / self.cell_size).floor();
+        (cell.x as i32, cell.y as i32)
+    }
+
+    /// Closest point no further than `range` from `center`.
+    pub fn closest_within(&self, center: Vec2, range: f32) -> Option<Vec2> {
+        let (min_x, min_y) = self.cell_of(center - Vec2::splat(range));
+        let (max_x, max_y) = self.cell_of(center + Vec2::splat(range));
+        let cells_in_range = (max_x - min_x + 1) as usize * (max_y - min_y + 1) as usize;
+
+        let mut closest_distance = range * range;
+        let mut closest_element: Option<Vec2> = None;
+        let mut visit = |bucket: &Vec<Vec2>| {
+            for &point in bucket {
+                let distance = point.distance_squared(center);
+                // Ties go to the lowest point, so the answer does not depend on bucket order.
+                let closer = match closest_element {
+                    None => distance <= closest_distance,
+                    Some(best) => {
+                        distance < closest_distance
+                            || (distance == closest_distance && (point.x, point.y) < (best.x, best.y))
+                    }
+                };
+                if closer {
+                    closest_distance = distance;
+                    closest_element = Some(point);
+                }
+            }
+        };
+        // A range wider than the occupied area is cheaper to answer bucket by bucket.
+        if cells_in_range > self.buckets.len() {
+            self.buckets.values().for_each(&mut visit);
+        } else {
+            for x in min_x..=max_x {
+                for y in min_y..=max_y {
+                    if let Some(bucket) = self.buckets.get(&(x, y)) {
+                        visit(bucket);
+                    }
+                }
+            }
+        }
+        closest_element
+    }
+}
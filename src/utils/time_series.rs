@@ -0,0 +1,152 @@
+use std::{collections::VecDeque, ops::RangeInclusive};
+
+/// Each level groups this many buckets of the level below into one.
+const LEVEL_FACTOR: u64 = 8;
+
+/// Min, max and mean of the samples pushed for a run of ticks.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Bucket {
+    /// First tick the bucket covers.
+    pub start: u64,
+    /// Number of ticks the bucket covers, samples or not.
+    pub span: u64,
+    pub min: f64,
+    pub max: f64,
+    sum: f64,
+    samples: u64,
+}
+
+impl Bucket {
+    fn new(start: u64, span: u64, value: f64) -> Self {
+        Self {
+            start,
+            span,
+            min: value,
+            max: value,
+            sum: value,
+            samples: 1,
+        }
+    }
+
+    fn merge(&mut self, other: &Bucket) {
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+        self.sum += other.sum;
+        self.samples += other.samples;
+    }
+
+    pub fn mean(&self) -> f64 {
+        self.sum / self.samples as f64
+    }
+
+    /// Last tick the bucket covers.
+    pub fn end(&self) -> u64 {
+        self.start + self.span - 1
+    }
+}
+
+#[derive(Clone)]
+struct Level {
+    span: u64,
+    buckets: VecDeque<Bucket>,
+}
+
+impl Level {
+    fn add(&mut self, bucket: &Bucket) {
+        let start = bucket.start - bucket.start % self.span;
+        match self.buckets.back_mut() {
+            Some(last) if last.start == start => last.merge(bucket),
+            _ => self.buckets.push_back(Bucket { start, span: self.span, ..*bucket }),
+        }
+    }
+}
+
+/// Samples of one value over a whole run, indexed by tick, kept at several
+/// resolutions: every level holds at most `capacity` buckets, each `LEVEL_FACTOR`
+/// times as long as the level below, and a coarser level is added whenever the
+/// coarsest one fills up, so the run as a whole is always covered.
+#[derive(Clone)]
+pub struct TimeSeries {
+    levels: Vec<Level>,
+    capacity: usize,
+    first: Option<u64>,
+}
+
+impl TimeSeries {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            levels: vec![Level {
+                span: 1,
+                buckets: VecDeque::with_capacity(capacity),
+            }],
+            capacity: capacity.max(LEVEL_FACTOR as usize),
+            first: None,
+        }
+    }
+
+    /// Records `value` at `tick`. Ticks come in increasing order, a repeated
+    /// tick is folded into the bucket it already has.
+    pub fn push(&mut self, tick: u64, value: f64) {
+        self.first.get_or_insert(tick);
+        let sample = Bucket::new(tick, 1, value);
+        for level in &mut self.levels {
+            level.add(&sample);
+        }
+
+        let top = self.levels.last().unwrap();
+        if top.buckets.len() > self.capacity {
+            let mut coarser = Level {
+                span: top.span * LEVEL_FACTOR,
+                buckets: VecDeque::with_capacity(self.capacity),
+            };
+            for bucket in &top.buckets {
+                coarser.add(bucket);
+            }
+            self.levels.push(coarser);
+        }
+        let capacity = self.capacity;
+        let finer = self.levels.len() - 1;
+        for level in &mut self.levels[..finer] {
+            while level.buckets.len() > capacity {
+                level.buckets.pop_front();
+            }
+        }
+    }
+
+    /// The most recent sample.
+    pub fn last(&self) -> Option<f64> {
+        self.levels[0].buckets.back().map(|bucket| bucket.mean())
+    }
+
+    /// Ticks from the first to the last sample.
+    pub fn ticks(&self) -> Option<RangeInclusive<u64>> {
+        Some(self.first?..=self.levels[0].buckets.back()?.start)
+    }
+
+    /// Buckets overlapping `ticks`, oldest first, from the finest level that
+    /// still holds the whole window in at most `max_points` buckets.
+    pub fn window(&self, ticks: RangeInclusive<u64>, max_points: usize) -> impl Iterator<Item = &Bucket> {
+        // Before the first sample there is nothing to resolve.
+        let (start, end) = (*ticks.start().max(&self.first.unwrap_or(0)), *ticks.end());
+        let level = self
+            .levels
+            .iter()
+            .find(|level| {
+                let holds_start = level.buckets.front().is_some_and(|first| first.start <= start);
+                let points = end.saturating_sub(start) / level.span + 1;
+                holds_start && points <= max_points as u64
+            })
+            .unwrap_or_else(|| self.levels.last().unwrap());
+        let first = level.buckets.partition_point(|bucket| bucket.end() < start);
+        level
+            .buckets
+            .range(first..)
+            .take_while(move |bucket| bucket.start <= end)
+    }
+
+    /// The last `ticks` ticks, up to the last sample.
+    pub fn recent(&self, ticks: u64, max_points: usize) -> impl Iterator<Item = &Bucket> {
+        let end = self.ticks().map_or(0, |ticks| *ticks.end());
+        self.window(end.saturating_sub(ticks.saturating_sub(1))..=end, max_points)
+    }
+}
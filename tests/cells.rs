@@ -0,0 +1,132 @@
+mod common;
+
+use bevy::{
+    prelude::*,
+    render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+};
+
+use simulation::{
+    actions::cells_plugin::{Food, SpawnCellEvent},
+    core::config::SimulationConfig,
+    entities::{cells::*, seed::Seed},
+};
+
+use common::TestWorld;
+
+#[test]
+fn cell_leaving_the_board_is_despawned() {
+    let mut world = TestWorld::new();
+    let cell = world.place_cell(SpawnCellEvent::new(Vec2::new(100., 0.)));
+    let id = *world.app.world.get::<CellId>(cell).unwrap();
+
+    world.move_to(cell, Vec2::new(400., 0.));
+    world.tick(1);
+
+    assert!(!world.exists(cell));
+    assert_eq!(world.count::<With<Cell>>(), 0);
+    assert_eq!(world.deaths(), &[(id, DeathCause::LeftBoard)]);
+}
+
+#[test]
+fn cell_on_the_board_stays() {
+    let mut world = TestWorld::new();
+    let cell = world.place_cell(SpawnCellEvent::new(Vec2::new(100., 0.)));
+
+    world.tick(10);
+
+    assert!(world.exists(cell));
+    assert!(world.deaths().is_empty());
+}
+
+#[test]
+fn cell_walks_to_a_seed_and_eats_it() {
+    let mut world = TestWorld::new();
+    let seed = world.place_seed(Vec2::new(60., 0.));
+    let cell = world.place_cell(SpawnCellEvent::new(Vec2::new(0., 0.)));
+    let id = *world.app.world.get::<CellId>(cell).unwrap();
+    let start_energy = world.energy(cell).unwrap();
+
+    world.tick(1);
+    let after_one_tick = world.position(cell).unwrap();
+    assert!(after_one_tick.x > 0., "the cell should head for the seed, it is at {after_one_tick}");
+    assert_eq!(after_one_tick.y, 0.);
+
+    world.tick(60);
+
+    assert!(!world.exists(seed));
+    assert_eq!(world.count::<With<Seed>>(), 0);
+    // The seed pays for far more than the walk there.
+    let seed_energy = world.app.world.resource::<SimulationConfig>().seed_energy;
+    assert!(world.energy(cell).unwrap() > start_energy + seed_energy / 2.);
+    assert_eq!(world.meals(), &[(id, Food::Seed)]);
+    assert!(world.deaths().is_empty());
+}
+
+#[test]
+fn cells_are_not_spawned_off_the_board() {
+    let mut world = TestWorld::new();
+    world.app.world.send_event(SpawnCellEvent::new(Vec2::new(1000., 0.)));
+    world.tick(1);
+
+    assert_eq!(world.count::<With<Cell>>(), 0);
+}
+
+#[test]
+fn a_mapped_board_too_small_for_seeds_gets_none() {
+    let path = std::env::temp_dir().join(format!("tiny-board-{}.png", std::process::id()));
+    let size = Extent3d {
+        width: 2,
+        height: 2,
+        depth_or_array_layers: 1,
+    };
+    let fertile = Image::new_fill(size, TextureDimension::D2, &[0, 255, 0, 255], TextureFormat::Rgba8UnormSrgb, default());
+    fertile.try_into_dynamic().unwrap().save(&path).unwrap();
+
+    let mut world = TestWorld::with_config(SimulationConfig {
+        rng_seed: 0,
+        board_width: 4.,
+        board_height: 40.,
+        board_image: Some(path.to_string_lossy().into_owned()),
+        initial_plants: 0,
+        ..default()
+    });
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(world.count::<With<Seed>>(), 0);
+}
+
+#[test]
+fn predation_leaves_the_uneaten_energy_in_the_corpse() {
+    let mut world = TestWorld::with_config(SimulationConfig {
+        rng_seed: 0,
+        seeds_grid: false,
+        initial_plants: 0,
+        // Nothing but the meal itself moves energy around.
+        cell_move_cost: 0.,
+        cell_division_energy: 1e6,
+        predator_attack_cost: 0.,
+        predator_success_chance: 1.,
+        predator_size_ratio: 10.,
+        ..default()
+    });
+    let prey = world.place_cell(SpawnCellEvent::new(Vec2::new(20., 0.)));
+    let prey_id = *world.app.world.get::<CellId>(prey).unwrap();
+    let predator = world.place_cell(SpawnCellEvent::predator(Vec2::new(0., 0.)));
+    let predator_id = *world.app.world.get::<CellId>(predator).unwrap();
+    let (prey_energy, predator_energy) = (world.energy(prey).unwrap(), world.energy(predator).unwrap());
+
+    for _ in 0..120 {
+        if !world.deaths().is_empty() {
+            break;
+        }
+        world.tick(1);
+    }
+
+    assert_eq!(world.deaths(), &[(prey_id, DeathCause::Predation)]);
+    assert_eq!(world.meals(), &[(predator_id, Food::Prey(prey_id))]);
+    let gain = world.app.world.resource::<SimulationConfig>().predator_energy_gain;
+    let eaten = world.energy(predator).unwrap() - predator_energy;
+    assert!((eaten - prey_energy * gain).abs() < 1e-3, "the predator got {eaten} of {prey_energy}");
+    assert_eq!(world.corpses().len(), 1);
+    assert!((eaten + world.corpses()[0] - prey_energy).abs() < 1e-3, "the corpse got {}", world.corpses()[0]);
+}
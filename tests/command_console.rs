@@ -0,0 +1,202 @@
+use std::{path::PathBuf, time::Duration};
+
+use bevy::{prelude::*, time::TimeUpdateStrategy};
+
+use simulation::{
+    core::{
+        config::SimulationConfig,
+        headless::build_headless_app,
+        lineage::LineageRecords,
+        replay::{ReplayState, UserInput},
+        simulation::SimulationTick,
+        species::{SpeciesId, SpeciesRegistry},
+    },
+    entities::cells::CellId,
+    ui::{command_console::*, log_console::*},
+};
+
+fn console_app() -> App {
+    let mut app = build_headless_app(SimulationConfig {
+        rng_seed: 7,
+        initial_cells: 20,
+        initial_predators: 0,
+        ..default()
+    });
+    // The headless app is already finished, so set the consoles up by hand.
+    LogConsolePlugin.build(&mut app);
+    CommandConsolePlugin.build(&mut app);
+    app
+}
+
+fn run(app: &mut App, line: &str) {
+    app.world.send_event(CommandLine(line.to_string()));
+    app.update();
+}
+
+fn tick(app: &App) -> u64 {
+    app.world.resource::<SimulationTick>().0
+}
+
+fn last_reply(app: &App) -> String {
+    let console = app.world.resource::<LogConsole>();
+    console.lines().rev().find(|line| line.kind == LogKind::Command).unwrap().message.clone()
+}
+
+#[test]
+fn commands_are_parsed() {
+    assert_eq!(
+        Command::parse("spawn cell 10 -20.5 count=50"),
+        Ok(Command::Spawn {
+            position: Vec2::new(10., -20.5),
+            predator: false,
+            count: 50,
+        })
+    );
+    assert_eq!(
+        Command::parse("  set   seeds.step 3.5 "),
+        Ok(Command::Set {
+            name: "seeds.step".to_string(),
+            value: 3.5,
+        })
+    );
+    assert_eq!(Command::parse("kill species 4"), Ok(Command::KillSpecies(4)));
+    assert_eq!(Command::parse("save world.ron"), Ok(Command::Save(PathBuf::from("world.ron"))));
+    assert_eq!(Command::parse("pause"), Ok(Command::Pause));
+
+    assert!(Command::parse("spawn cell ten 20").is_err());
+    assert!(Command::parse("spawn cell 1 2 size=3").is_err());
+    assert!(Command::parse(&format!("spawn cell 0 0 count={MAX_SPAWN_COUNT}")).is_ok());
+    assert_eq!(
+        Command::parse(&format!("spawn cell 0 0 count={}", MAX_SPAWN_COUNT + 1)),
+        Err(format!("at most {MAX_SPAWN_COUNT} cells at once, usage: spawn cell|predator <x> <y> [count=<n>]"))
+    );
+    assert!(Command::parse("spawn cell 0 0 count=4000000000").is_err());
+    assert!(Command::parse("kill species").is_err());
+    assert!(Command::parse("jump").is_err());
+    assert!(Command::parse("").is_err());
+}
+
+#[test]
+fn completions_follow_the_command_being_typed() {
+    let parameters = SimulationConfig::default().parameter_names();
+    assert_eq!(completions("s", &parameters, &[]), ["spawn", "set", "save"]);
+    assert_eq!(completions("spawn ", &parameters, &[]), ["cell", "predator"]);
+    assert_eq!(completions("spawn cell 1 2 ", &parameters, &[]), ["count="]);
+    assert_eq!(completions("set cells.s", &parameters, &[]), ["cells.speed", "cells.sensor_range"]);
+    assert_eq!(completions("kill species 1", &parameters, &[1, 12, 13, 20]), ["12", "13"]);
+    assert!(completions("pause", &parameters, &[]).is_empty());
+}
+
+#[test]
+fn every_parameter_name_can_be_set() {
+    let mut config = SimulationConfig::default();
+    for name in config.parameter_names() {
+        let value = config.parameter(&name).unwrap();
+        assert_eq!(config.set_parameter(&name, value), Ok(()), "{name}");
+    }
+}
+
+#[test]
+fn pause_holds_the_world_and_its_inputs() {
+    let mut app = console_app();
+    for _ in 0..10 {
+        app.update();
+    }
+    run(&mut app, "pause");
+    let paused_at = tick(&app);
+
+    run(&mut app, "set cells.speed 99");
+    assert_eq!(last_reply(&app), "Set cells.speed to 99");
+    for _ in 0..5 {
+        app.update();
+    }
+    assert_eq!(tick(&app), paused_at);
+    assert_ne!(app.world.resource::<SimulationConfig>().cell_speed, 99.);
+
+    run(&mut app, "resume");
+    app.update();
+    assert!(tick(&app) > paused_at);
+    assert_eq!(app.world.resource::<SimulationConfig>().cell_speed, 99.);
+    let recorded = app.world.resource::<ReplayState>().recording(tick(&app));
+    assert!(recorded.inputs.iter().any(|recorded| matches!(
+        &recorded.input,
+        UserInput::SetParameter { name, .. } if name == "cells.speed"
+    )));
+}
+
+#[test]
+fn invalid_commands_change_nothing() {
+    let mut app = console_app();
+    app.update();
+
+    run(&mut app, "set cells.speed -1");
+    assert_eq!(last_reply(&app), "`cells.speed` must be positive, got -1");
+    run(&mut app, "set cells.colour 1");
+    assert_eq!(last_reply(&app), "unknown parameter `cells.colour`");
+    run(&mut app, "kill species 9999");
+    assert_eq!(last_reply(&app), "No living species #9999");
+
+    let recorded = app.world.resource::<ReplayState>().recording(tick(&app));
+    assert!(recorded.inputs.is_empty());
+}
+
+#[test]
+fn spawn_and_kill_species() {
+    let mut app = console_app();
+    app.update();
+
+    run(&mut app, "spawn cell 0 0 count=15");
+    app.update();
+    let mut cells = app.world.query::<&SpeciesId>();
+    assert_eq!(cells.iter(&app.world).count(), 35);
+
+    let species = app.world.resource::<SpeciesRegistry>().living().next().unwrap().id;
+    run(&mut app, &format!("kill species {}", species.0));
+    app.update();
+    assert!(cells.iter(&app.world).all(|of| *of != species));
+}
+
+/// Cells that died and cells the lineage still has alive, by id.
+fn deaths(app: &App) -> (Vec<CellId>, Vec<CellId>) {
+    let lineage = app.world.resource::<LineageRecords>();
+    (0..lineage.next_id())
+        .map(CellId)
+        .partition(|id| lineage.get(*id).is_some_and(|record| !record.is_alive()))
+}
+
+#[test]
+fn pausing_does_not_lose_deaths() {
+    let config = SimulationConfig {
+        rng_seed: 5,
+        initial_cells: 40,
+        initial_predators: 12,
+        ..default()
+    };
+    let mut straight = build_headless_app(config.clone());
+    for _ in 0..300 {
+        straight.update();
+    }
+
+    let mut app = build_headless_app(config);
+    LogConsolePlugin.build(&mut app);
+    CommandConsolePlugin.build(&mut app);
+    // Events are only dropped on frames with a fixed update, so make every frame one.
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(20)));
+    while tick(&app) < 300 {
+        app.update();
+        if tick(&app) % 3 == 0 {
+            run(&mut app, "pause");
+            for _ in 0..4 {
+                app.update();
+            }
+            run(&mut app, "resume");
+        }
+    }
+
+    let (died, alive) = deaths(&app);
+    assert!(!died.is_empty());
+    assert_eq!((died, alive.clone()), deaths(&straight));
+    let mut living: Vec<CellId> = app.world.query::<&CellId>().iter(&app.world).copied().collect();
+    living.sort();
+    assert_eq!(living, alive);
+}
@@ -0,0 +1,130 @@
+//! The headless `App` that sweeps and replays run, for driving the simulation
+//! tick by tick from tests.
+
+use bevy::prelude::*;
+
+use simulation::{
+    actions::{cells_plugin::*, corpses_plugin::SpawnCorpseEvent, seeds_plugin::*},
+    core::{config::SimulationConfig, headless::build_headless_app, lineage::CellDiedEvent},
+    entities::{cells::*, seed::Seed},
+};
+
+/// Every `CellDiedEvent` sent since the harness was built, in order.
+#[derive(Resource, Default)]
+pub struct DeathLog(pub Vec<(CellId, DeathCause)>);
+
+/// Every `CellAteEvent` sent since the harness was built, in order.
+#[derive(Resource, Default)]
+pub struct MealLog(pub Vec<(CellId, Food)>);
+
+/// Energy of every corpse asked for since the harness was built, in order.
+#[derive(Resource, Default)]
+pub struct CorpseLog(pub Vec<f32>);
+
+pub struct TestWorld {
+    pub app: App,
+}
+
+impl TestWorld {
+    /// An empty 500×500 board: no seed grid, plants or initial cells.
+    pub fn new() -> Self {
+        Self::with_config(SimulationConfig {
+            rng_seed: 0,
+            seeds_grid: false,
+            initial_plants: 0,
+            ..default()
+        })
+    }
+
+    /// Builds the headless app and runs the startup pass, which spawns the boards.
+    pub fn with_config(config: SimulationConfig) -> Self {
+        let mut app = build_headless_app(config);
+        app.init_resource::<DeathLog>()
+            .init_resource::<MealLog>()
+            .init_resource::<CorpseLog>()
+            .add_systems(Last, (record_deaths_system, record_meals_system, record_corpses_system));
+        app.update();
+        Self { app }
+    }
+
+    /// Runs `ticks` updates.
+    pub fn tick(&mut self, ticks: usize) {
+        for _ in 0..ticks {
+            self.app.update();
+        }
+    }
+
+    /// Spawns a cell at `position` and returns it; takes one tick, during
+    /// which the new cell does not move yet.
+    pub fn place_cell(&mut self, event: SpawnCellEvent) -> Entity {
+        self.app.world.send_event(event);
+        self.tick(1);
+        let newest = self
+            .app
+            .world
+            .query::<(Entity, &CellId)>()
+            .iter(&self.app.world)
+            .max_by_key(|(_, id)| **id)
+            .map(|(entity, _)| entity);
+        newest.expect("the cell was not spawned, is the position on the board?")
+    }
+
+    /// Spawns a seed at `position` and returns it; takes one tick.
+    pub fn place_seed(&mut self, position: Vec2) -> Entity {
+        self.app.world.send_event(SpawnSeedEvent { position });
+        self.tick(1);
+        self.app
+            .world
+            .query_filtered::<(Entity, &Transform), With<Seed>>()
+            .iter(&self.app.world)
+            .find(|(_, transform)| transform.translation.xy() == position)
+            .map(|(entity, _)| entity)
+            .expect("the seed was not spawned, is the position on the board?")
+    }
+
+    /// Teleports an entity, keeping its depth.
+    pub fn move_to(&mut self, entity: Entity, position: Vec2) {
+        let mut transform = self.app.world.get_mut::<Transform>(entity).expect("entity has no transform");
+        transform.translation = position.extend(transform.translation.z);
+    }
+
+    pub fn position(&self, entity: Entity) -> Option<Vec2> {
+        self.app.world.get::<Transform>(entity).map(|transform| transform.translation.xy())
+    }
+
+    pub fn exists(&self, entity: Entity) -> bool {
+        self.app.world.get_entity(entity).is_some()
+    }
+
+    pub fn energy(&self, entity: Entity) -> Option<f32> {
+        self.app.world.get::<Cell>(entity).map(|cell| cell.energy)
+    }
+
+    pub fn count<F: bevy::ecs::query::QueryFilter>(&mut self) -> usize {
+        self.app.world.query_filtered::<(), F>().iter(&self.app.world).count()
+    }
+
+    pub fn deaths(&self) -> &[(CellId, DeathCause)] {
+        &self.app.world.resource::<DeathLog>().0
+    }
+
+    pub fn meals(&self) -> &[(CellId, Food)] {
+        &self.app.world.resource::<MealLog>().0
+    }
+
+    pub fn corpses(&self) -> &[f32] {
+        &self.app.world.resource::<CorpseLog>().0
+    }
+}
+
+fn record_deaths_system(mut events: EventReader<CellDiedEvent>, mut log: ResMut<DeathLog>) {
+    log.0.extend(events.read().map(|ev| (ev.id, ev.cause)));
+}
+
+fn record_meals_system(mut events: EventReader<CellAteEvent>, mut log: ResMut<MealLog>) {
+    log.0.extend(events.read().map(|ev| (ev.id, ev.food)));
+}
+
+fn record_corpses_system(mut events: EventReader<SpawnCorpseEvent>, mut log: ResMut<CorpseLog>) {
+    log.0.extend(events.read().map(|ev| ev.energy));
+}
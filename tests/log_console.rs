@@ -0,0 +1,41 @@
+use bevy::{
+    log::tracing_subscriber::Registry,
+    prelude::*,
+    utils::tracing::subscriber::with_default,
+};
+
+use simulation::{
+    core::{config::SimulationConfig, headless::build_headless_app},
+    ui::log_console::*,
+};
+
+// One test, as captured lines go through a buffer shared by every app in the process.
+#[test]
+fn console_collects_tracing_output_and_simulation_events() {
+    let mut app = build_headless_app(SimulationConfig {
+        rng_seed: 7,
+        initial_cells: 10,
+        initial_predators: 2,
+        ..default()
+    });
+    // The headless app is already finished, so set the console up by hand.
+    LogConsolePlugin.build(&mut app);
+
+    with_default(capture_logs(Box::new(Registry::default())), || {
+        warn!(cells = 10, "console check");
+    });
+    for _ in 0..300 {
+        app.update();
+    }
+
+    let console = app.world.resource::<LogConsole>();
+    let captured = console
+        .lines()
+        .find(|line| line.kind == LogKind::Warn)
+        .expect("the warning is collected");
+    assert_eq!(captured.message, "console check cells=10");
+    assert_eq!(captured.target, "log_console");
+    let count = |kind| console.lines().filter(|line| line.kind == kind).count();
+    assert!(count(LogKind::Birth) >= 12);
+    assert!(count(LogKind::Eat) > 0);
+}
@@ -0,0 +1,52 @@
+use bevy::prelude::*;
+use proptest::prelude::*;
+
+use simulation::{
+    core::network::{BatchedNetworks, CapNetwork},
+    entities::cells::Cell,
+};
+
+/// Ordinary coordinates mixed with the values that trip up a normalisation:
+/// zeros, denormals, huge values, infinities and NaN.
+fn coordinate() -> impl Strategy<Value = f32> {
+    prop_oneof![
+        4 => -1e3f32..1e3,
+        1 => Just(0.),
+        1 => Just(-0.),
+        1 => Just(f32::MIN_POSITIVE / 2.),
+        1 => Just(f32::MAX),
+        1 => Just(f32::INFINITY),
+        1 => Just(f32::NAN),
+    ]
+}
+
+/// A brain's position and target, sometimes the same point.
+fn brain() -> impl Strategy<Value = (Vec2, Vec2)> {
+    let point = || (coordinate(), coordinate()).prop_map(|(x, y)| Vec2::new(x, y));
+    prop_oneof![
+        3 => (point(), point()),
+        1 => point().prop_map(|point| (point, point)),
+    ]
+}
+
+fn bits(value: Vec3) -> [u32; 3] {
+    value.to_array().map(f32::to_bits)
+}
+
+proptest! {
+    #[test]
+    fn batched_results_match_activate_bit_for_bit(brains in prop::collection::vec(brain(), 0..40)) {
+        let mut batched = BatchedNetworks::default();
+        let mut expected = Vec::new();
+        for (index, (position, target)) in brains.iter().enumerate() {
+            let entity = Entity::from_raw(index as u32);
+            let mut cell = Cell { energy: 0. };
+            batched.push(entity, &cell, position.extend(0.), *target);
+            expected.push((entity, bits(cell.activate(position.extend(0.), *target))));
+        }
+        batched.evaluate();
+
+        let results: Vec<_> = batched.results().map(|(entity, output)| (entity, bits(output))).collect();
+        prop_assert_eq!(results, expected);
+    }
+}
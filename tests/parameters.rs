@@ -0,0 +1,131 @@
+use bevy::prelude::*;
+use bevy_rapier2d::dynamics::Damping;
+
+use simulation::{
+    core::{
+        config::SimulationConfig,
+        headless::build_headless_app,
+        replay::{ReplayState, UserInput},
+        simulation::SimulationTick,
+    },
+    entities::{cells::*, seed::Seed, terrain::*, world_board::WorldBoard},
+};
+
+#[test]
+fn parameters_read_back_what_was_set() {
+    let whole_numbers = ["initial", "seed_interval", "day_length", "days_per_season"];
+    for name in SimulationConfig::default().parameter_names() {
+        let mut config = SimulationConfig::default();
+        let whole = whole_numbers.iter().any(|suffix| name.ends_with(suffix));
+        let value = match name.as_str() {
+            "seeds.grid" => 0.,
+            _ if whole => 3.,
+            _ => 0.25,
+        };
+        config.set_parameter(&name, value).unwrap();
+        assert_eq!(config.parameter(&name), Some(value), "{name}");
+    }
+    assert_eq!(SimulationConfig::default().parameter("cells.colour"), None);
+}
+
+#[test]
+fn out_of_range_parameters_are_turned_down() {
+    let mut config = SimulationConfig::default();
+    let unchanged = config.clone();
+    for name in config.parameter_names() {
+        for value in [f64::NAN, f64::INFINITY, -1.] {
+            assert!(config.set_parameter(&name, value).is_err(), "{name} = {value}");
+        }
+    }
+    assert_eq!(config, unchanged);
+
+    for (name, value) in [
+        ("cells.mutation_rate", 1.5),
+        ("seeds.grid", 0.5),
+        ("cells.initial", 2.5),
+        ("clock.days_per_season", 0.),
+        ("fields.pheromones.diffusion", 0.3),
+    ] {
+        assert!(config.set_parameter(name, value).is_err(), "{name} = {value}");
+    }
+    assert_eq!(
+        config.set_parameter("predators.energy_gain", 2.),
+        Err("`predators.energy_gain` must be between 0 and 1, got 2".to_string())
+    );
+}
+
+#[test]
+fn terrain_that_cells_cannot_cross_is_turned_down() {
+    let region = |move_cost| TerrainRegion {
+        min: [0., 0.],
+        max: [10., 10.],
+        move_cost,
+    };
+    assert_eq!(region(0.5).validate(), Ok(()));
+
+    for move_cost in [0., -2., f32::NAN, f32::INFINITY] {
+        assert!(region(move_cost).validate().is_err(), "{move_cost}");
+        let config = SimulationConfig {
+            world: WorldLayout {
+                terrain: vec![region(2.), region(move_cost)],
+                ..default()
+            },
+            ..default()
+        };
+        assert!(config.validate().is_err(), "{move_cost}");
+    }
+}
+
+#[test]
+fn seed_damping_changes_live() {
+    let mut app = build_headless_app(SimulationConfig {
+        rng_seed: 3,
+        ..default()
+    });
+    app.update();
+
+    app.world.send_event(UserInput::SetParameter {
+        name: "seeds.damping".to_string(),
+        value: 0.5,
+    });
+    app.update();
+
+    let mut seeds = app.world.query_filtered::<&Damping, With<Seed>>();
+    assert!(seeds.iter(&app.world).count() > 0);
+    assert!(seeds.iter(&app.world).all(|damping| damping.linear_damping == 0.5));
+}
+
+#[test]
+fn restarting_uses_the_parameters_read_at_start() {
+    let mut app = build_headless_app(SimulationConfig {
+        rng_seed: 3,
+        initial_cells: 5,
+        ..default()
+    });
+    app.update();
+    for (name, value) in [("board.width", 200.), ("cells.initial", 2.)] {
+        app.world.send_event(UserInput::SetParameter {
+            name: name.to_string(),
+            value,
+        });
+    }
+    app.update();
+
+    let edge = Vec2::new(150., 0.);
+    let mut boards = app.world.query::<&WorldBoard>();
+    let mut cells = app.world.query_filtered::<(), (With<Cell>, Without<Predator>)>();
+    assert!(boards.single(&app.world).contains(edge));
+    assert_eq!(cells.iter(&app.world).count(), 5);
+
+    app.world.resource_mut::<ReplayState>().restart();
+    app.update();
+
+    assert_eq!(app.world.resource::<SimulationTick>().0, 0);
+    assert!(!boards.single(&app.world).contains(edge));
+    // The initial cells are asked for at the restart and spawn on the first tick.
+    app.update();
+    assert_eq!(cells.iter(&app.world).count(), 2);
+    let replay = &app.world.resource::<ReplayState>().replay;
+    assert_eq!(replay.config.board_width, 200.);
+    assert!(replay.inputs.is_empty());
+}
@@ -0,0 +1,184 @@
+use bevy::prelude::*;
+
+use simulation::{
+    core::{
+        config::SimulationConfig,
+        headless::{build_headless_app, run_headless_replay},
+        replay::*,
+        simulation::SimulationTick,
+    },
+    entities::{cells::*, seed::Seed, terrain::*},
+};
+
+fn config() -> SimulationConfig {
+    SimulationConfig {
+        rng_seed: 7,
+        initial_cells: 10,
+        initial_predators: 1,
+        seeds_step: 16.,
+        ..default()
+    }
+}
+
+/// Cells, predators and seeds, with the positions of all cells sorted by id.
+fn population(app: &mut App) -> (usize, usize, Vec<(CellId, Vec2)>) {
+    let predators = app.world.query_filtered::<(), With<Predator>>().iter(&app.world).count();
+    let seeds = app.world.query_filtered::<(), With<Seed>>().iter(&app.world).count();
+    let mut cells: Vec<(CellId, Vec2)> = app
+        .world
+        .query::<(&CellId, &Transform)>()
+        .iter(&app.world)
+        .map(|(id, transform)| (*id, transform.translation.truncate()))
+        .collect();
+    cells.sort_by_key(|(id, _)| *id);
+    (predators, seeds, cells)
+}
+
+fn tick(app: &App) -> u64 {
+    app.world.resource::<SimulationTick>().0
+}
+
+/// Seeks to `target` and runs until the world got there.
+fn seek(app: &mut App, target: u64) {
+    let now = tick(app);
+    app.world.resource_mut::<ReplayState>().seek(target, now);
+    while app.world.resource::<ReplayState>().seek.is_some() {
+        app.update();
+    }
+}
+
+/// Runs to `ticks`, sending some user inputs on the way.
+fn record(app: &mut App, ticks: u64) {
+    while tick(app) < ticks {
+        let input = match tick(app) {
+            20 => Some(UserInput::SpawnCell {
+                position: [50., 50.],
+                predator: false,
+            }),
+            40 => Some(UserInput::AddObstacle(ObstacleShape::Circle {
+                center: [-100., 0.],
+                radius: 30.,
+            })),
+            60 => Some(UserInput::SpawnCell {
+                position: [-50., -50.],
+                predator: true,
+            }),
+            _ => None,
+        };
+        if let Some(input) = input {
+            app.world.send_event(input);
+        }
+        app.update();
+    }
+}
+
+#[test]
+fn recording_replays_to_the_same_world() {
+    let mut app = build_headless_app(config());
+    record(&mut app, 150);
+    let recorded = app.world.resource::<ReplayState>().recording(tick(&app));
+    assert_eq!(recorded.inputs.len(), 3);
+    assert_eq!(recorded.length, 150);
+
+    let mut replayed = build_headless_app(recorded.config.clone());
+    replayed.insert_resource(ReplayState::playing(recorded.clone()));
+    for _ in 0..150 {
+        replayed.update();
+    }
+
+    assert_eq!(replayed.world.resource::<SimulationConfig>().world.obstacles.len(), 1);
+    assert_eq!(population(&mut replayed), population(&mut app));
+    let samples = run_headless_replay(recorded);
+    assert_eq!(samples.len(), 150);
+}
+
+#[test]
+fn user_inputs_are_ignored_while_playing() {
+    let mut recorded = build_headless_app(config());
+    record(&mut recorded, 30);
+    let replay = recorded.world.resource::<ReplayState>().recording(30);
+
+    let mut app = build_headless_app(replay.config.clone());
+    app.insert_resource(ReplayState::playing(replay));
+    app.update();
+    app.world.send_event(UserInput::SpawnCell {
+        position: [0., 0.],
+        predator: true,
+    });
+    for _ in 1..30 {
+        app.update();
+    }
+
+    assert_eq!(population(&mut app), population(&mut recorded));
+}
+
+#[test]
+fn seeking_back_restores_the_earlier_world() {
+    let mut reference = build_headless_app(config());
+    record(&mut reference, 80);
+    let at_80 = population(&mut reference);
+
+    let mut app = build_headless_app(config());
+    record(&mut app, 150);
+    seek(&mut app, 80);
+
+    assert_eq!(tick(&app), 80);
+    assert_eq!(population(&mut app), at_80);
+}
+
+#[test]
+fn rewinding_goes_on_like_the_original_run() {
+    let mut reference = build_headless_app(config());
+    let mut straight = Vec::new();
+    for ticks in 1..=450 {
+        record(&mut reference, ticks);
+        straight.push(population(&mut reference));
+    }
+
+    let mut app = build_headless_app(config());
+    record(&mut app, 300);
+    seek(&mut app, 50);
+    assert_eq!(tick(&app), 50);
+    assert_eq!(population(&mut app), straight[49]);
+
+    // Past the end of the recording too, where the run goes back to recording.
+    while tick(&app) < 450 {
+        app.update();
+        assert_eq!(population(&mut app), straight[tick(&app) as usize - 1], "tick {}", tick(&app));
+    }
+}
+
+#[test]
+fn loading_turns_down_an_out_of_range_config() {
+    let path = std::env::temp_dir().join(format!("replay-load-test-{}.ron", std::process::id()));
+    let mut replay = Replay {
+        config: config(),
+        ..default()
+    };
+    replay.save(&path).unwrap();
+    assert_eq!(Replay::load(&path).unwrap().config, replay.config);
+
+    replay.config.predator_success_chance = 1.5;
+    replay.save(&path).unwrap();
+    assert_eq!(
+        Replay::load(&path).unwrap_err().to_string(),
+        "`predators.success_chance` must be between 0 and 1, got 1.5"
+    );
+
+    replay.config = config();
+    replay.inputs.push(RecordedInput {
+        tick: 3,
+        input: UserInput::AddTerrain(TerrainRegion {
+            min: [0., 0.],
+            max: [10., 10.],
+            move_cost: 0.,
+        }),
+    });
+    replay.save(&path).unwrap();
+    assert_eq!(
+        Replay::load(&path).unwrap_err().to_string(),
+        "terrain `move_cost` must be positive, got 0"
+    );
+
+    std::fs::remove_file(&path).unwrap();
+}
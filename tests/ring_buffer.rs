@@ -0,0 +1,107 @@
+use proptest::prelude::*;
+
+use simulation::utils::ring_buffer::{RingBuffer, RollingStats};
+
+#[test]
+fn push_drops_the_oldest_and_returns_it() {
+    let mut buffer = RingBuffer::new(3);
+    assert_eq!(buffer.push(1), None);
+    buffer.extend([2, 3]);
+    assert_eq!(buffer.push(4), Some(1));
+
+    assert_eq!(buffer.iter().copied().collect::<Vec<_>>(), [2, 3, 4]);
+    assert_eq!(buffer.iter().rev().copied().collect::<Vec<_>>(), [4, 3, 2]);
+    assert_eq!(buffer.iter().len(), 3);
+    assert_eq!((buffer[0], buffer[2]), (2, 4));
+    assert_eq!(buffer.get_last(), Some(&4));
+}
+
+#[test]
+fn zero_capacity_keeps_nothing() {
+    let mut buffer = RingBuffer::new(0);
+    assert_eq!(buffer.push(1), Some(1));
+    assert!(buffer.is_empty());
+}
+
+#[test]
+fn shrinking_drops_the_oldest() {
+    let mut buffer: RingBuffer<u32> = (0..10).collect();
+    assert_eq!(buffer.capacity(), 10);
+
+    assert_eq!(buffer.shrink_capacity(4), [0, 1, 2, 3, 4, 5]);
+    assert_eq!(buffer.into_iter().collect::<Vec<_>>(), [6, 7, 8, 9]);
+}
+
+#[test]
+fn clear_keeps_the_capacity() {
+    let mut stats: RollingStats = (0..5).map(f64::from).collect();
+    stats.clear();
+
+    assert!(stats.is_empty());
+    assert_eq!(stats.capacity(), 5);
+    assert_eq!((stats.mean(), stats.min(), stats.percentile(0.5)), (None, None, None));
+}
+
+#[test]
+fn serde_round_trip() {
+    let mut buffer = RingBuffer::new(3);
+    buffer.extend(["a", "b", "c", "d"].map(String::from));
+
+    let text = ron::to_string(&buffer).unwrap();
+    assert_eq!(ron::from_str::<RingBuffer<String>>(&text).unwrap(), buffer);
+
+    let stats: RollingStats = ron::from_str(&ron::to_string(&RollingStats::from_iter([1., 2., 3.])).unwrap()).unwrap();
+    assert_eq!(stats.mean(), Some(2.));
+}
+
+#[test]
+fn deserializing_trims_to_the_capacity() {
+    let buffer: RingBuffer<u32> = ron::from_str("(buffer: [1, 2, 3, 4], capacity: 2)").unwrap();
+    assert_eq!(buffer.iter().copied().collect::<Vec<_>>(), [3, 4]);
+}
+
+#[test]
+fn percentile_interpolates_between_samples() {
+    let stats: RollingStats = [4., 1., 3., 2.].into_iter().collect();
+    assert_eq!(stats.percentile(0.), Some(1.));
+    assert_eq!(stats.percentile(0.5), Some(2.5));
+    assert_eq!(stats.percentile(1.), Some(4.));
+}
+
+fn close(a: f64, b: f64) -> bool {
+    (a - b).abs() <= 1e-6 * (1. + a.abs().max(b.abs()))
+}
+
+proptest! {
+    #[test]
+    fn holds_the_last_capacity_items(capacity in 0usize..20, items in prop::collection::vec(any::<i32>(), 0..100)) {
+        let mut buffer = RingBuffer::new(capacity);
+        buffer.extend(items.iter().copied());
+
+        let kept = &items[items.len().saturating_sub(capacity)..];
+        prop_assert_eq!(buffer.len(), kept.len());
+        prop_assert_eq!(buffer.iter().copied().collect::<Vec<_>>(), kept);
+        prop_assert_eq!(buffer.get_last(), kept.last());
+    }
+
+    #[test]
+    fn rolling_stats_match_the_window(
+        capacity in 1usize..30,
+        values in prop::collection::vec(-1e3f64..1e3, 1..200),
+        shrink_to in 1usize..30,
+    ) {
+        let mut stats = RollingStats::new(capacity);
+        stats.extend(values.iter().copied());
+        stats.shrink_capacity(shrink_to);
+        stats.extend(values.iter().rev().copied());
+
+        let window: Vec<f64> = stats.iter().copied().collect();
+        let count = window.len() as f64;
+        let mean = window.iter().sum::<f64>() / count;
+        let variance = window.iter().map(|value| (value - mean).powi(2)).sum::<f64>() / count;
+        prop_assert!(close(stats.mean().unwrap(), mean));
+        prop_assert!(close(stats.variance().unwrap(), variance));
+        prop_assert_eq!(stats.min().unwrap(), window.iter().copied().fold(f64::INFINITY, f64::min));
+        prop_assert_eq!(stats.max().unwrap(), window.iter().copied().fold(f64::NEG_INFINITY, f64::max));
+    }
+}
@@ -0,0 +1,121 @@
+use std::{collections::BTreeMap, fs};
+
+use simulation::core::{config::SimulationConfig, sweep::*};
+
+fn definition(parameters: &[(&str, &[f64])]) -> SweepDefinition {
+    SweepDefinition {
+        ticks: 5,
+        repetitions: 2,
+        base_seed: 40,
+        threads: Some(2),
+        base: SimulationConfig {
+            initial_cells: 4,
+            initial_predators: 1,
+            initial_plants: 0,
+            ..Default::default()
+        },
+        parameters: parameters
+            .iter()
+            .map(|(name, values)| (name.to_string(), values.to_vec()))
+            .collect::<BTreeMap<_, _>>(),
+    }
+}
+
+#[test]
+fn runs_cover_the_grid_in_order() {
+    let runs = definition(&[("seeds.step", &[16., 32., 64.]), ("cells.radius", &[10., 15.])])
+        .runs()
+        .unwrap();
+
+    assert_eq!(runs.len(), 12);
+    let mut expected = Vec::new();
+    // Parameters vary in name order, the last one fastest, and repetitions innermost.
+    for radius in [10., 15.] {
+        for step in [16., 32., 64.] {
+            for repetition in 0..2 {
+                expected.push((radius, step, repetition, 40 + repetition as u64));
+            }
+        }
+    }
+    for (index, (run, (radius, step, repetition, seed))) in runs.iter().zip(expected).enumerate() {
+        assert_eq!(run.index, index);
+        assert_eq!(run.repetition, repetition);
+        assert_eq!(
+            run.parameters,
+            [("cells.radius".to_string(), radius), ("seeds.step".to_string(), step)]
+        );
+        assert_eq!(run.config.cell_radius, radius as f32);
+        assert_eq!(run.config.seeds_step, step as f32);
+        assert_eq!(run.config.rng_seed, seed);
+        assert_eq!(run.config.initial_cells, 4);
+    }
+}
+
+#[test]
+fn a_sweep_without_parameters_repeats_the_base() {
+    let runs = definition(&[]).runs().unwrap();
+    assert_eq!(runs.len(), 2);
+    assert!(runs.iter().all(|run| run.parameters.is_empty()));
+    assert_eq!(runs.iter().map(|run| run.config.rng_seed).collect::<Vec<_>>(), [40, 41]);
+}
+
+#[test]
+fn bad_grids_are_turned_down() {
+    assert!(definition(&[("seeds.step", &[])]).runs().is_err());
+    assert!(definition(&[("cells.colour", &[1.])]).runs().is_err());
+    assert!(definition(&[("cells.speed", &[1., -1.])]).runs().is_err());
+}
+
+#[test]
+fn summary_has_a_row_per_run() {
+    let out = std::env::temp_dir().join(format!("sweep-test-{}", std::process::id()));
+    let definition = definition(&[("cells.speed", &[0.5, 2.])]);
+    let finished = std::sync::Mutex::new(Vec::new());
+    let summary = run_sweep(&definition, &out, |done, total| finished.lock().unwrap().push((done, total))).unwrap();
+    let mut finished = finished.into_inner().unwrap();
+    // Two workers may report out of order.
+    finished.sort();
+    assert_eq!(finished, [(1, 4), (2, 4), (3, 4), (4, 4)]);
+
+    let text = fs::read_to_string(&summary).unwrap();
+    let mut lines = text.lines();
+    assert_eq!(
+        lines.next(),
+        Some("run,repetition,rng_seed,cells.speed,final_cells,final_predators,final_seeds,peak_cells,mean_cells")
+    );
+    let rows: Vec<Vec<&str>> = lines.map(|line| line.split(',').collect()).collect();
+    assert_eq!(rows.len(), 4);
+    for (index, row) in rows.iter().enumerate() {
+        let speed = if index < 2 { "0.5" } else { "2" };
+        let seed = (40 + index % 2).to_string();
+        assert_eq!(row[..4], [index.to_string().as_str(), &(index % 2).to_string(), &seed, speed]);
+
+        // The final counts are the last line of the run's own file.
+        let run = fs::read_to_string(out.join(format!("run_{index:04}.csv"))).unwrap();
+        assert_eq!(run.lines().count(), 1 + definition.ticks as usize);
+        let last: Vec<&str> = run.lines().last().unwrap().split(',').collect();
+        assert_eq!(last[0], definition.ticks.to_string());
+        assert_eq!(row[4..7], last[1..4]);
+    }
+
+    fs::remove_dir_all(&out).unwrap();
+}
+
+#[test]
+fn loading_turns_down_an_out_of_range_base() {
+    let path = std::env::temp_dir().join(format!("sweep-load-test-{}.ron", std::process::id()));
+    let load = |base: &str| {
+        fs::write(&path, format!("(ticks: 5, base: ({base}), parameters: {{\"cells.speed\": [1]}})")).unwrap();
+        SweepDefinition::load(&path).map_err(|err| err.to_string())
+    };
+
+    assert!(load("initial_cells: 4").is_ok());
+    assert_eq!(
+        load("predator_success_chance: 1.5").unwrap_err(),
+        "`predators.success_chance` must be between 0 and 1, got 1.5"
+    );
+    assert!(load("board_width: -10").is_err());
+    assert!(load("seeds_step: 0").is_err());
+
+    fs::remove_file(&path).unwrap();
+}
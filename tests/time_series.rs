@@ -0,0 +1,46 @@
+use simulation::utils::time_series::TimeSeries;
+
+#[test]
+fn recent_window_has_every_tick() {
+    let mut series = TimeSeries::new(100);
+    for tick in 0..1000 {
+        series.push(tick, tick as f64);
+    }
+
+    let buckets: Vec<_> = series.recent(50, 100).collect();
+    assert_eq!(buckets.len(), 50);
+    assert!(buckets.iter().all(|bucket| bucket.span == 1));
+    assert_eq!(buckets[0].start, 950);
+    assert_eq!(series.last(), Some(999.));
+}
+
+#[test]
+fn whole_run_is_kept_at_a_coarser_resolution() {
+    let mut series = TimeSeries::new(100);
+    for tick in 0..100_000 {
+        series.push(tick, (tick % 10) as f64);
+    }
+
+    assert_eq!(series.ticks(), Some(0..=99_999));
+    let buckets: Vec<_> = series.window(0..=99_999, 100).collect();
+    assert!(buckets.len() <= 100);
+    assert_eq!(buckets[0].start, 0);
+    assert!(buckets.last().unwrap().end() >= 99_999);
+    for bucket in &buckets {
+        assert_eq!((bucket.min, bucket.max), (0., 9.));
+        assert!((bucket.mean() - 4.5).abs() < 0.5);
+    }
+}
+
+#[test]
+fn old_window_comes_from_the_finest_level_that_still_holds_it() {
+    let mut series = TimeSeries::new(100);
+    for tick in 0..10_000 {
+        series.push(tick, 1.);
+    }
+
+    // The single ticks are gone, 8-tick buckets reach back 800 ticks.
+    let buckets: Vec<_> = series.window(9_304..=9_383, 100).collect();
+    assert_eq!(buckets.len(), 10);
+    assert!(buckets.iter().all(|bucket| bucket.span == 8 && bucket.mean() == 1.));
+}